@@ -0,0 +1,68 @@
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, Ident};
+
+// HomeScreen becomes HOME_SCREEN
+fn constant_name(variant: &Ident) -> Ident {
+    let mut name = String::new();
+    for (i, c) in variant.to_string().chars().enumerate() {
+        if c.is_uppercase() && i > 0 && !name.ends_with('_') {
+            name.push('_');
+        }
+        name.push(c.to_ascii_uppercase());
+    }
+    Ident::new(&name, variant.span())
+}
+
+// Index constants and name lookups for an enum of states, the names match the variants
+#[proc_macro_derive(FsmStates)]
+pub fn derive_fsm_states(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let ident = &input.ident;
+
+    let variants = match &input.data {
+        Data::Enum(data) => &data.variants,
+        _ => return syn::Error::new(Span::call_site(), "FsmStates can only be derived for enums").to_compile_error().into(),
+    };
+    if let Some(variant) = variants.iter().find(|variant| !matches!(variant.fields, Fields::Unit)) {
+        return syn::Error::new_spanned(variant, "FsmStates variants can't carry data").to_compile_error().into();
+    }
+    // The constants are positions, an explicit discriminant would make `as usize` disagree with them
+    if let Some(variant) = variants.iter().find(|variant| variant.discriminant.is_some()) {
+        return syn::Error::new_spanned(variant, "FsmStates variants can't have explicit discriminants").to_compile_error().into();
+    }
+
+    let constants = variants.iter().enumerate().map(|(index, variant)| {
+        let constant = constant_name(&variant.ident);
+        quote! { pub const #constant: usize = #index; }
+    });
+    let names = variants.iter().map(|variant| variant.ident.to_string());
+    let arms = variants.iter().enumerate().map(|(index, variant)| {
+        let variant = &variant.ident;
+        quote! { #ident::#variant => #index }
+    });
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    quote! {
+        impl #impl_generics #ident #ty_generics #where_clause {
+            #(#constants)*
+
+            pub const NAMES: &'static [&'static str] = &[#(#names),*];
+
+            pub fn index(&self) -> usize {
+                match self {
+                    #(#arms),*
+                }
+            }
+
+            pub fn name(index: usize) -> Option<&'static str> {
+                Self::NAMES.get(index).copied()
+            }
+
+            pub fn from_name(name: &str) -> Option<usize> {
+                Self::NAMES.iter().position(|&state| state == name)
+            }
+        }
+    }.into()
+}
@@ -0,0 +1,171 @@
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::{FsmError, StateId, StateMachine};
+
+impl<T: Clone, E> StateMachine<T, E> {
+    pub fn unreachable_states(&self, from: impl Into<StateId>) -> Vec<usize> {
+        let from = from.into().0;
+        let mut visited = vec![false; self.num_states];
+        let mut queue = VecDeque::new();
+
+        if self.contains_state(from) {
+            visited[from] = true;
+            queue.push_back(from);
+        }
+
+        while let Some(src) = queue.pop_front() {
+            for dst in self.successors(src) {
+                if !visited[dst] {
+                    visited[dst] = true;
+                    queue.push_back(dst);
+                }
+            }
+        }
+
+        (0..self.num_states)
+            .filter(|&index| !visited[index] && self.states[index].is_some())
+            .collect()
+    }
+
+    pub fn dead_end_states(&self) -> Vec<usize> {
+        (0..self.num_states)
+            .filter(|&index| self.states[index].is_some() && !self.final_states[index])
+            .filter(|&index| !self.transitions.has_outgoing(index))
+            .collect()
+    }
+
+    pub fn out_degree(&self, index: impl Into<StateId>) -> Result<usize, FsmError> {
+        let index = index.into().0;
+        self.state(index)?;
+        Ok(self.transitions.outgoing(index).count())
+    }
+
+    pub fn in_degree(&self, index: impl Into<StateId>) -> Result<usize, FsmError> {
+        let index = index.into().0;
+        self.state(index)?;
+        Ok(self.transitions_iter().filter(|&(_, dst, _)| dst == index).count())
+    }
+
+    pub fn shortest_path(&self, from: impl Into<StateId>, to: impl Into<StateId>) -> Option<Vec<usize>> {
+        let from = from.into().0;
+        let to = to.into().0;
+        self.state(from).ok()?;
+        self.state(to).ok()?;
+
+        let mut parent: Vec<Option<usize>> = vec![None; self.num_states];
+        let mut visited = vec![false; self.num_states];
+        let mut queue = VecDeque::new();
+        visited[from] = true;
+        queue.push_back(from);
+
+        while let Some(src) = queue.pop_front() {
+            if src == to {
+                // Walk the parents back to the start
+                let mut path = vec![to];
+                let mut index = to;
+                while let Some(prev) = parent[index] {
+                    path.push(prev);
+                    index = prev;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for dst in self.successors(src) {
+                if !visited[dst] {
+                    visited[dst] = true;
+                    parent[dst] = Some(src);
+                    queue.push_back(dst);
+                }
+            }
+        }
+        None
+    }
+
+    pub fn has_cycle(&self) -> bool {
+        self.find_cycle().is_some()
+    }
+
+    // States along the first cycle found, in transition order, a self-transition is a cycle of one
+    pub fn find_cycle(&self) -> Option<Vec<usize>> {
+        #[derive(Clone, Copy, PartialEq)]
+        enum Mark {
+            New,
+            OnStack,
+            Done,
+        }
+
+        let mut marks = vec![Mark::New; self.num_states];
+        for start in (0..self.num_states).filter(|&index| self.states[index].is_some()) {
+            if marks[start] != Mark::New {
+                continue;
+            }
+
+            // Iterative DFS, each frame keeps the successors of its state still to visit
+            let mut path = vec![start];
+            let mut pending: Vec<Vec<usize>> = vec![self.successors(start).collect()];
+            marks[start] = Mark::OnStack;
+
+            while let Some(successors) = pending.last_mut() {
+                match successors.pop() {
+                    Some(dst) => match marks[dst] {
+                        Mark::OnStack => {
+                            let position = path.iter().position(|&index| index == dst).expect("Failed to acquire cycle start");
+                            return Some(path[position..].to_vec());
+                        },
+                        Mark::New => {
+                            marks[dst] = Mark::OnStack;
+                            path.push(dst);
+                            pending.push(self.successors(dst).collect());
+                        },
+                        Mark::Done => {},
+                    },
+                    None => {
+                        let done = path.pop().expect("Failed to acquire path");
+                        marks[done] = Mark::Done;
+                        pending.pop();
+                    },
+                }
+            }
+        }
+        None
+    }
+
+    // States ordered so every transition points forward, isolated states come first as roots
+    pub fn topological_order(&self) -> Result<Vec<usize>, FsmError> {
+        let mut in_degree = vec![0; self.num_states];
+        for src in (0..self.num_states).filter(|&index| self.states[index].is_some()) {
+            for dst in self.successors(src) {
+                in_degree[dst] += 1;
+            }
+        }
+
+        let mut queue: VecDeque<usize> = (0..self.num_states)
+            .filter(|&index| self.states[index].is_some() && in_degree[index] == 0)
+            .collect();
+        let mut order = Vec::new();
+        while let Some(src) = queue.pop_front() {
+            order.push(src);
+            for dst in self.successors(src) {
+                in_degree[dst] -= 1;
+                if in_degree[dst] == 0 {
+                    queue.push_back(dst);
+                }
+            }
+        }
+
+        // Any state left over sits on or behind a cycle
+        if order.len() < self.states_iter().count() {
+            Err(FsmError::CyclicGraph)
+        } else {
+            Ok(order)
+        }
+    }
+
+    fn successors(&self, src: usize) -> impl Iterator<Item = usize> + '_ {
+        self.transitions.outgoing(src)
+            .map(|(dst, _)| dst)
+            .filter(move |&dst| self.states[dst].is_some())
+    }
+}
@@ -0,0 +1,194 @@
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::future::Future;
+use core::pin::Pin;
+
+use crate::{FsmError, FsmErrorKind, StepOutcome};
+
+pub type FsmFuture<'a, E = FsmError> = Pin<Box<dyn Future<Output = Result<(), E>> + 'a>>;
+pub type AsyncStateCallback<T, E> = dyn for<'a> Fn(&'a mut T) -> FsmFuture<'a, E>;
+pub type AsyncTransCheckCallback<T> = dyn Fn(&T) -> bool;
+pub type AsyncTransDoneCallback<T, E> = dyn for<'a> Fn(&'a mut T) -> FsmFuture<'a, E>;
+
+pub struct AsyncState<T: 'static, E: 'static = FsmError> {
+    pub name: String,
+    pub init: Rc<AsyncStateCallback<T, E>>,
+    pub exec: Rc<AsyncStateCallback<T, E>>,
+}
+
+impl<T, E> AsyncState<T, E> {
+    pub fn new<'b>(name: impl Into<alloc::borrow::Cow<'b, str>>,
+                   init: impl for<'a> Fn(&'a mut T) -> FsmFuture<'a, E> + 'static,
+                   exec: impl for<'a> Fn(&'a mut T) -> FsmFuture<'a, E> + 'static) -> AsyncState<T, E> {
+        AsyncState {
+            name: name.into().into_owned(),
+            init: Rc::new(init),
+            exec: Rc::new(exec),
+        }
+    }
+}
+
+pub struct AsyncTransition<T: 'static, E: 'static = FsmError> {
+    pub name: String,
+    pub src: usize,
+    pub dst: usize,
+    pub priority: i32,
+    pub check: Rc<AsyncTransCheckCallback<T>>,
+    pub done: Rc<AsyncTransDoneCallback<T, E>>,
+}
+
+impl<T, E> AsyncTransition<T, E> {
+    pub fn new<'b>(name: impl Into<alloc::borrow::Cow<'b, str>>,
+                   src: usize,
+                   dst: usize,
+                   check: impl Fn(&T) -> bool + 'static,
+                   done: impl for<'a> Fn(&'a mut T) -> FsmFuture<'a, E> + 'static) -> AsyncTransition<T, E> {
+        AsyncTransition::with_priority(name, src, dst, 0, check, done)
+    }
+
+    pub fn with_priority<'b>(name: impl Into<alloc::borrow::Cow<'b, str>>,
+                             src: usize,
+                             dst: usize,
+                             priority: i32,
+                             check: impl Fn(&T) -> bool + 'static,
+                             done: impl for<'a> Fn(&'a mut T) -> FsmFuture<'a, E> + 'static) -> AsyncTransition<T, E> {
+        AsyncTransition {
+            name: name.into().into_owned(),
+            src,
+            dst,
+            priority,
+            check: Rc::new(check),
+            done: Rc::new(done),
+        }
+    }
+}
+
+// A reduced StateMachine::run: init once per entry, exec, then the first passing transition by
+// priority and insertion order has its done awaited before the move. There are no exit actions,
+// events, internal, global or default transitions, final states, error callbacks or history,
+// an error is returned as Errored and leaves the machine in the state it happened in
+pub struct AsyncStateMachine<T: 'static, E: 'static = FsmError> {
+    data: T,
+    states: Vec<Option<AsyncState<T, E>>>,
+    num_states: usize,
+    transitions: Vec<Vec<Option<AsyncTransition<T, E>>>>,
+    transition_order: Vec<Vec<usize>>,
+    active_state: Option<usize>,
+    active_state_initialized: bool,
+}
+
+impl<T, E> AsyncStateMachine<T, E> {
+    pub fn new(data: T, max_states: usize) -> AsyncStateMachine<T, E> {
+        AsyncStateMachine {
+            data,
+            states: (0..max_states).map(|_| None).collect(),
+            num_states: 0,
+            transitions: (0..max_states).map(|_| (0..max_states).map(|_| None).collect()).collect(),
+            transition_order: (0..max_states).map(|_| Vec::new()).collect(),
+            active_state: None,
+            active_state_initialized: false,
+        }
+    }
+
+    pub fn data(&self) -> &T {
+        &self.data
+    }
+
+    pub fn data_mut(&mut self) -> &mut T {
+        &mut self.data
+    }
+
+    pub fn current_state_index(&self) -> Option<usize> {
+        self.active_state
+    }
+
+    pub fn add_state(&mut self, s: AsyncState<T, E>) -> Result<usize, FsmError> {
+        if self.states.iter().flatten().any(|state| state.name == s.name) {
+            Err(FsmError::DuplicateStateName)
+        } else if self.num_states >= self.states.len() {
+            Err(FsmError::MaxNumberOfStatesExceeded)
+        } else {
+            let index = self.num_states;
+            self.states[index] = Some(s);
+            self.num_states += 1;
+            Ok(index)
+        }
+    }
+
+    pub fn add_transition(&mut self, t: AsyncTransition<T, E>, src: usize, dst: usize) -> Result<(), FsmError> {
+        if src >= self.num_states || dst >= self.num_states {
+            return Err(FsmError::TransitionIndexOutOfBounds);
+        } else if src == dst {
+            return Err(FsmError::AddTransitionSrcDstStatesEqual);
+        } else if t.src != src || t.dst != dst {
+            return Err(FsmError::TransitionEndpointsMismatch);
+        }
+
+        if self.transitions[src][dst].is_none() {
+            self.transition_order[src].push(dst);
+        }
+        self.transitions[src][dst] = Some(t);
+
+        // Higher priority first, the stable sort keeps insertion order for ties
+        let transitions = &self.transitions[src];
+        self.transition_order[src].sort_by_key(|&dst| {
+            core::cmp::Reverse(transitions[dst].as_ref().map_or(i32::MIN, |transition| transition.priority))
+        });
+        Ok(())
+    }
+
+    pub fn set_active_state(&mut self, index: usize) -> Result<(), FsmError> {
+        if index >= self.num_states {
+            Err(FsmError::StateIndexOutOfBounds)
+        } else if self.states[index].is_none() {
+            Err(FsmError::StateIsEmpty)
+        } else {
+            self.active_state = Some(index);
+            Ok(())
+        }
+    }
+
+    pub async fn run(&mut self) -> StepOutcome<E> {
+        let active_state_index = match self.active_state {
+            Some(index) => index,
+            None => return StepOutcome::Errored(FsmError::NoActiveState.into()),
+        };
+        let active_state = self.states[active_state_index].as_ref().expect("Failed to acquire active state");
+
+        if !self.active_state_initialized {
+            if let Err(e) = (active_state.init)(&mut self.data).await {
+                return StepOutcome::Errored(FsmErrorKind::User(e));
+            }
+            self.active_state_initialized = true;
+        }
+        if let Err(e) = (active_state.exec)(&mut self.data).await {
+            return StepOutcome::Errored(FsmErrorKind::User(e));
+        }
+
+        let transitions = &self.transitions[active_state_index];
+        let next = self.transition_order[active_state_index].iter()
+            .copied()
+            .find(|&dst| transitions[dst].as_ref().is_some_and(|transition| (transition.check)(&self.data)));
+        let next_state_index = match next {
+            Some(dst) => dst,
+            None => return StepOutcome::Stayed(active_state_index),
+        };
+
+        let transition = transitions[next_state_index].as_ref().expect("Failed to acquire transition");
+        if let Err(e) = (transition.done)(&mut self.data).await {
+            return StepOutcome::Errored(FsmErrorKind::User(e));
+        }
+        let via = transition.name.clone();
+
+        self.active_state = Some(next_state_index);
+        self.active_state_initialized = false;
+
+        StepOutcome::Transitioned {
+            from: active_state_index,
+            to: next_state_index,
+            via,
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use crate::{Destination, FsmError, FsmErrorKind, State, StateId, StateMachine, Transition};
+
+pub struct StateMachineBuilder<T: 'static + Clone, E: 'static = FsmError> {
+    sm: StateMachine<T, E>,
+    initial: Option<usize>,
+    error: Option<FsmError>,
+}
+
+impl<T: Clone, E> StateMachineBuilder<T, E> {
+    pub fn new(data: T, max_states: usize) -> StateMachineBuilder<T, E> {
+        StateMachineBuilder {
+            sm: StateMachine::new(data, max_states),
+            initial: None,
+            error: None,
+        }
+    }
+
+    pub fn state<'b>(mut self,
+                     name: impl Into<alloc::borrow::Cow<'b, str>>,
+                     init: impl Fn(&State<T, E>, &mut T) -> Result<(), E> + 'static,
+                     exec: impl Fn(&State<T, E>, &mut T) -> Result<(), E> + 'static
+    ) -> StateMachineBuilder<T, E> {
+        if self.error.is_none() {
+            if let Err(e) = self.sm.add_state(State::new(name, init, exec)) {
+                self.error = Some(e);
+            }
+        }
+        self
+    }
+
+    pub fn transition<'b>(mut self,
+                          src: impl Into<StateId>,
+                          dst: impl Into<StateId>,
+                          name: impl Into<alloc::borrow::Cow<'b, str>>,
+                          check: impl Fn(&Transition<T, E>, &T) -> bool + 'static,
+                          done: impl Fn(&Transition<T, E>, &mut T) -> Result<(), E> + 'static
+    ) -> StateMachineBuilder<T, E> {
+        if self.error.is_none() {
+            let (src, dst) = (src.into(), dst.into());
+            let t = Transition::new(name, src, dst, check, done);
+            if let Err(e) = self.sm.add_transition(t, src, dst) {
+                self.error = Some(e);
+            }
+        }
+        self
+    }
+
+    pub fn initial(mut self, index: impl Into<StateId>) -> StateMachineBuilder<T, E> {
+        let index = index.into().0;
+        self.initial = Some(index);
+        self
+    }
+
+    pub fn on_error(mut self,
+                    init: impl Fn(FsmErrorKind<E>, &mut T) -> Option<Destination> + 'static,
+                    exec: impl Fn(FsmErrorKind<E>, &mut T) -> Option<Destination> + 'static
+    ) -> StateMachineBuilder<T, E> {
+        self.sm.set_error_callbacks(init, exec);
+        self
+    }
+
+    pub fn build(mut self) -> Result<StateMachine<T, E>, FsmError> {
+        if let Some(e) = self.error {
+            return Err(e);
+        }
+        if let Some(index) = self.initial {
+            self.sm.set_initial(index)?;
+            self.sm.set_active_state(index)?;
+        }
+        Ok(self.sm)
+    }
+}
@@ -0,0 +1,60 @@
+use alloc::rc::Rc;
+use core::cell::Cell;
+use core::time::Duration;
+use std::time::Instant;
+
+use crate::StateMachine;
+
+pub trait Clock {
+    fn now(&self) -> Instant;
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+// Manually advanced clock, clones share the same time so a test can keep a handle after set_clock
+#[derive(Clone, Debug)]
+pub struct MockClock {
+    now: Rc<Cell<Instant>>,
+}
+
+impl MockClock {
+    pub fn new() -> MockClock {
+        MockClock { now: Rc::new(Cell::new(Instant::now())) }
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        self.now.set(self.now.get() + duration);
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> MockClock {
+        MockClock::new()
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        self.now.get()
+    }
+}
+
+impl<T: Clone, E> StateMachine<T, E> {
+    pub fn set_clock(&mut self, clock: impl Clock + 'static) {
+        // Restart the time in the active state on the new clock
+        let now = clock.now();
+        self.clock = Rc::new(clock);
+        if self.entered_at.is_some() {
+            self.entered_at = Some(now);
+        }
+        #[cfg(feature = "metrics")]
+        self.metrics.restart(now);
+    }
+}
@@ -0,0 +1,78 @@
+use alloc::vec::Vec;
+use core::fmt::{Debug, Formatter};
+
+use crate::{State, StateMachine, Transition};
+
+struct Callback;
+
+impl Debug for Callback {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_str("<fn>")
+    }
+}
+
+struct Adjacency<'a, T: 'static + Clone, E: 'static>(&'a StateMachine<T, E>);
+
+impl<T: Clone, E> Debug for Adjacency<'_, T, E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let sm = self.0;
+        let mut map = f.debug_map();
+        for src in 0..sm.num_states {
+            let edges: Vec<(usize, &str)> = sm.transitions_iter()
+                .filter(|&(from, _, _)| from == src)
+                .map(|(_, dst, transition)| (dst, transition.name.as_str()))
+                .collect();
+            if !edges.is_empty() {
+                map.entry(&src, &edges);
+            }
+        }
+        map.finish()
+    }
+}
+
+impl<T: Clone, E> Debug for StateMachine<T, E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let states: Vec<(usize, &str)> = self.states_iter()
+            .map(|(index, state)| (index, state.name.as_str()))
+            .collect();
+
+        f.debug_struct("StateMachine")
+            .field("num_states", &self.num_states)
+            .field("states", &states)
+            .field("active_state", &self.active_state)
+            .field("active_state_initialized", &self.active_state_initialized)
+            .field("transitions", &Adjacency(self))
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T, E> Debug for State<T, E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("State")
+            .field("name", &self.name)
+            .field("init", &Callback)
+            .field("exec", &Callback)
+            .field("on_exit", &self.on_exit.as_ref().map(|_| Callback))
+            .field("max_retries", &self.max_retries)
+            .finish()
+    }
+}
+
+impl<T: Clone, E> Debug for Transition<T, E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Transition")
+            .field("name", &self.name)
+            .field("src", &self.src)
+            .field("dst", &self.dst)
+            .field("priority", &self.priority)
+            .field("weight", &self.weight)
+            .field("event", &self.event)
+            .field("timeout", &self.timeout)
+            .field("enabled", &self.enabled)
+            .field("check", &Callback)
+            .field("explain", &self.explain.as_ref().map(|_| Callback))
+            .field("done", &Callback)
+            .field("done_between", &self.done_between.as_ref().map(|_| Callback))
+            .finish()
+    }
+}
@@ -0,0 +1,151 @@
+use heapless::{String, Vec};
+
+use crate::{FsmError, FsmErrorKind};
+
+pub type EmbeddedStateCallback<T, E> = fn(&mut T) -> Result<(), E>;
+pub type EmbeddedCheckCallback<T> = fn(&T) -> bool;
+
+pub struct EmbeddedState<T, E, const NAME: usize> {
+    pub name: String<NAME>,
+    pub init: EmbeddedStateCallback<T, E>,
+    pub exec: EmbeddedStateCallback<T, E>,
+}
+
+pub struct EmbeddedTransition<T, E, const NAME: usize> {
+    // Assigned in insertion order, it doesn't move when a higher priority transition is added
+    pub id: usize,
+    pub name: String<NAME>,
+    pub src: usize,
+    pub dst: usize,
+    pub priority: i32,
+    pub check: EmbeddedCheckCallback<T>,
+    pub done: EmbeddedStateCallback<T, E>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum EmbeddedOutcome<E = FsmError> {
+    Transitioned { from: usize, to: usize, via: usize },
+    Stayed(usize),
+    Errored(FsmErrorKind<E>),
+}
+
+// Allocation-free machine with every capacity fixed at compile time: S states, M transitions and
+// names up to NAME bytes. Callbacks are plain function pointers so nothing needs to be boxed.
+pub struct EmbeddedStateMachine<T, const S: usize, const M: usize, const NAME: usize = 16, E = FsmError> {
+    data: T,
+    states: Vec<EmbeddedState<T, E, NAME>, S>,
+    // Sorted by descending priority, insertion order breaks ties
+    transitions: Vec<EmbeddedTransition<T, E, NAME>, M>,
+    active_state: Option<usize>,
+    active_state_initialized: bool,
+}
+
+impl<T, const S: usize, const M: usize, const NAME: usize, E> EmbeddedStateMachine<T, S, M, NAME, E> {
+    pub fn new(data: T) -> EmbeddedStateMachine<T, S, M, NAME, E> {
+        EmbeddedStateMachine {
+            data,
+            states: Vec::new(),
+            transitions: Vec::new(),
+            active_state: None,
+            active_state_initialized: false,
+        }
+    }
+
+    pub fn data(&self) -> &T {
+        &self.data
+    }
+
+    pub fn data_mut(&mut self) -> &mut T {
+        &mut self.data
+    }
+
+    pub fn current_state_index(&self) -> Option<usize> {
+        self.active_state
+    }
+
+    pub fn state(&self, index: usize) -> Result<&EmbeddedState<T, E, NAME>, FsmError> {
+        self.states.get(index).ok_or(FsmError::StateIndexOutOfBounds)
+    }
+
+    pub fn transition(&self, id: usize) -> Result<&EmbeddedTransition<T, E, NAME>, FsmError> {
+        self.transitions.iter().find(|transition| transition.id == id).ok_or(FsmError::TransitionIndexOutOfBounds)
+    }
+
+    pub fn add_state(&mut self,
+                     name: &str,
+                     init: EmbeddedStateCallback<T, E>,
+                     exec: EmbeddedStateCallback<T, E>) -> Result<usize, FsmError> {
+        let name: String<NAME> = String::try_from(name).map_err(|_| FsmError::NameTooLong)?;
+        if self.states.iter().any(|state| state.name == name) {
+            return Err(FsmError::DuplicateStateName);
+        }
+
+        self.states.push(EmbeddedState { name, init, exec }).map_err(|_| FsmError::MaxNumberOfStatesExceeded)?;
+        Ok(self.states.len() - 1)
+    }
+
+    pub fn add_transition(&mut self,
+                          name: &str,
+                          src: usize,
+                          dst: usize,
+                          priority: i32,
+                          check: EmbeddedCheckCallback<T>,
+                          done: EmbeddedStateCallback<T, E>) -> Result<usize, FsmError> {
+        if src >= self.states.len() || dst >= self.states.len() {
+            return Err(FsmError::TransitionIndexOutOfBounds);
+        }
+        if src == dst {
+            return Err(FsmError::AddTransitionSrcDstStatesEqual);
+        }
+        let name: String<NAME> = String::try_from(name).map_err(|_| FsmError::NameTooLong)?;
+
+        // Nothing is ever removed, so the count so far is a unique id
+        let id = self.transitions.len();
+        let position = self.transitions.iter()
+            .position(|transition| transition.priority < priority)
+            .unwrap_or(self.transitions.len());
+        self.transitions.insert(position, EmbeddedTransition { id, name, src, dst, priority, check, done })
+            .map_err(|_| FsmError::MaxNumberOfTransitionsExceeded)?;
+        Ok(id)
+    }
+
+    pub fn set_active_state(&mut self, index: usize) -> Result<(), FsmError> {
+        self.state(index)?;
+        self.active_state = Some(index);
+        Ok(())
+    }
+
+    pub fn run(&mut self) -> EmbeddedOutcome<E> {
+        let active_state_index = match self.active_state {
+            Some(index) => index,
+            None => return EmbeddedOutcome::Errored(FsmError::NoActiveState.into()),
+        };
+        let active_state = &self.states[active_state_index];
+
+        if !self.active_state_initialized {
+            if let Err(e) = (active_state.init)(&mut self.data) {
+                return EmbeddedOutcome::Errored(FsmErrorKind::User(e));
+            }
+            self.active_state_initialized = true;
+        }
+        if let Err(e) = (active_state.exec)(&mut self.data) {
+            return EmbeddedOutcome::Errored(FsmErrorKind::User(e));
+        }
+
+        let fired = self.transitions.iter()
+            .position(|transition| transition.src == active_state_index && (transition.check)(&self.data));
+        let index = match fired {
+            Some(index) => index,
+            None => return EmbeddedOutcome::Stayed(active_state_index),
+        };
+
+        let transition = &self.transitions[index];
+        if let Err(e) = (transition.done)(&mut self.data) {
+            return EmbeddedOutcome::Errored(FsmErrorKind::User(e));
+        }
+
+        self.active_state = Some(transition.dst);
+        self.active_state_initialized = false;
+        EmbeddedOutcome::Transitioned { from: active_state_index, to: transition.dst, via: transition.id }
+    }
+}
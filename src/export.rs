@@ -0,0 +1,145 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use crate::StateMachine;
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern", "false", "fn", "for",
+    "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return", "self", "static", "struct",
+    "super", "trait", "true", "type", "unsafe", "use", "where", "while",
+];
+
+fn escape(name: &str) -> String {
+    name.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+// A snake case identifier for the name, made unique among the ones already taken
+fn identifier(name: &str, taken: &mut Vec<String>) -> String {
+    let lowered: String = name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+        .collect();
+    let mut ident = lowered.split('_').filter(|part| !part.is_empty()).collect::<Vec<&str>>().join("_");
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) || KEYWORDS.contains(&ident.as_str()) {
+        ident.insert(0, '_');
+    }
+
+    let mut unique = ident.clone();
+    let mut suffix = 1;
+    while taken.contains(&unique) {
+        suffix += 1;
+        unique = format!("{}_{}", ident, suffix);
+    }
+    taken.push(unique.clone());
+    unique
+}
+
+impl<T: Clone, E> StateMachine<T, E> {
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph fsm {\n");
+
+        for (index, state) in self.states_iter() {
+            let _ = write!(dot, "    s{} [label=\"{}\"", index, escape(&state.name));
+            if self.active_state == Some(index) {
+                dot.push_str(", style=filled, fillcolor=lightblue");
+            }
+            dot.push_str("];\n");
+        }
+
+        for (src, dst, transition) in self.transitions_iter() {
+            let _ = writeln!(dot, "    s{} -> s{} [label=\"{}\"];", src, dst, escape(&transition.name));
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    pub fn to_mermaid(&self) -> String {
+        let mut mermaid = String::from("stateDiagram-v2\n");
+
+        // Names may contain spaces, so states are referenced through aliases
+        for (index, state) in self.states_iter() {
+            let _ = writeln!(mermaid, "    state \"{}\" as s{}", escape(&state.name), index);
+        }
+
+        if let Some(index) = self.active_state.filter(|&index| self.states[index].is_some()) {
+            let _ = writeln!(mermaid, "    [*] --> s{}", index);
+        }
+
+        for (src, dst, transition) in self.transitions_iter() {
+            if self.states[src].is_some() && self.states[dst].is_some() {
+                let _ = writeln!(mermaid, "    s{} --> s{} : {}", src, dst, transition.name);
+            }
+        }
+
+        mermaid
+    }
+
+    pub fn to_plantuml(&self) -> String {
+        let mut uml = String::from("@startuml\n");
+
+        for (index, state) in self.states_iter() {
+            let _ = writeln!(uml, "state \"{}\" as s{}", escape(&state.name), index);
+        }
+
+        if let Some(index) = self.active_state.or(self.initial_state).filter(|&index| self.states[index].is_some()) {
+            let _ = writeln!(uml, "[*] --> s{}", index);
+        }
+
+        for (src, dst, transition) in self.transitions_iter() {
+            let _ = writeln!(uml, "s{} --> s{} : {}", src, dst, transition.name);
+        }
+
+        uml.push_str("@enduml\n");
+        uml
+    }
+
+    // Skeleton source that rebuilds the machine's shape, with stub callbacks to fill in
+    pub fn generate_rust(&self, module_name: &str) -> String {
+        let mut taken = vec![String::from("sm"), String::from("data")];
+        let states: Vec<(usize, String)> = self.states_iter()
+            .map(|(index, state)| (index, identifier(&state.name, &mut taken)))
+            .collect();
+        let state_ident = |index: usize| states.iter().find(|(state, _)| *state == index).map(|(_, ident)| ident.as_str());
+        let transitions: Vec<(usize, usize, &str, String)> = self.transitions_iter()
+            .filter(|&(src, dst, _)| self.states[src].is_some() && self.states[dst].is_some())
+            .map(|(src, dst, transition)| (src, dst, transition.name.as_str(), identifier(&transition.name, &mut taken)))
+            .collect();
+
+        let mut rust = String::new();
+        let _ = writeln!(rust, "mod {} {{", module_name);
+        rust.push_str("    use call_fsm::{*};\n\n");
+        rust.push_str("    // Replace with the machine's data type\n");
+        rust.push_str("    pub type DataType = ();\n");
+
+        for (_, ident) in states.iter() {
+            for callback in ["init", "exec"] {
+                let _ = write!(rust, "\n    fn {}_{}(_s: &State<DataType>, _data: &mut DataType) -> Result<(), FsmError> {{\n", ident, callback);
+                rust.push_str("        Ok(())\n    }\n");
+            }
+        }
+        for (_, _, _, ident) in transitions.iter() {
+            let _ = write!(rust, "\n    fn {}_check(_t: &Transition<DataType>, _data: &DataType) -> bool {{\n", ident);
+            rust.push_str("        true\n    }\n");
+            let _ = write!(rust, "\n    fn {}_done(_t: &Transition<DataType>, _data: &mut DataType) -> Result<(), FsmError> {{\n", ident);
+            rust.push_str("        Ok(())\n    }\n");
+        }
+
+        rust.push_str("\n    pub fn build(data: DataType) -> StateMachine<DataType> {\n");
+        let _ = writeln!(rust, "        let mut sm: StateMachine<DataType> = StateMachine::new(data, {});", states.len());
+        for (index, ident) in states.iter() {
+            let name = self.states[*index].as_ref().map_or("", |state| state.name.as_str());
+            let _ = writeln!(rust, "        let {0} = sm.add_state(State::new(\"{1}\", {0}_init, {0}_exec)).expect(\"Failed to add state\");",
+                             ident, escape(name));
+        }
+        for (src, dst, name, ident) in transitions.iter() {
+            let (src, dst) = (state_ident(*src).unwrap_or_default(), state_ident(*dst).unwrap_or_default());
+            let _ = writeln!(rust, "        sm.place_transition(Transition::new(\"{0}\", {1}, {2}, {3}_check, {3}_done)).expect(\"Failed to add transition\");",
+                             escape(name), src, dst, ident);
+        }
+        rust.push_str("        sm\n    }\n}\n");
+        rust
+    }
+}
@@ -0,0 +1,213 @@
+use crate::{FsmError, FsmErrorKind};
+
+pub type FixedStateCallback<T, E> = fn(&mut T) -> Result<(), E>;
+pub type FixedCheckCallback<T> = fn(&T) -> bool;
+
+fn noop<T, E>(_data: &mut T) -> Result<(), E> {
+    Ok(())
+}
+
+pub struct FixedState<T, E = FsmError> {
+    pub name: &'static str,
+    pub init: FixedStateCallback<T, E>,
+    pub exec: FixedStateCallback<T, E>,
+    pub exit: FixedStateCallback<T, E>,
+}
+
+impl<T, E> FixedState<T, E> {
+    pub fn new(name: &'static str, init: FixedStateCallback<T, E>, exec: FixedStateCallback<T, E>) -> FixedState<T, E> {
+        FixedState { name, init, exec, exit: noop }
+    }
+
+    pub fn with_exit(name: &'static str,
+                     init: FixedStateCallback<T, E>,
+                     exec: FixedStateCallback<T, E>,
+                     exit: FixedStateCallback<T, E>) -> FixedState<T, E> {
+        FixedState { name, init, exec, exit }
+    }
+}
+
+pub struct FixedTransition<T, E = FsmError> {
+    pub name: &'static str,
+    pub src: usize,
+    pub dst: usize,
+    pub priority: i32,
+    pub check: FixedCheckCallback<T>,
+    pub done: FixedStateCallback<T, E>,
+}
+
+impl<T, E> FixedTransition<T, E> {
+    pub fn new(name: &'static str,
+               src: usize,
+               dst: usize,
+               check: FixedCheckCallback<T>,
+               done: FixedStateCallback<T, E>) -> FixedTransition<T, E> {
+        FixedTransition::with_priority(name, src, dst, 0, check, done)
+    }
+
+    pub fn with_priority(name: &'static str,
+                         src: usize,
+                         dst: usize,
+                         priority: i32,
+                         check: FixedCheckCallback<T>,
+                         done: FixedStateCallback<T, E>) -> FixedTransition<T, E> {
+        FixedTransition { name, src, dst, priority, check, done }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum FixedOutcome<E = FsmError> {
+    Transitioned { from: usize, to: usize, via: &'static str },
+    Stayed(usize),
+    Errored(FsmErrorKind<E>),
+}
+
+// Fixed-capacity machine keeping its states and transitions inline. Names are static strings and
+// callbacks plain function pointers, so it runs without an allocator.
+pub struct FixedStateMachine<T, const N: usize, E = FsmError> {
+    data: T,
+    states: [Option<FixedState<T, E>>; N],
+    num_states: usize,
+    transitions: [[Option<FixedTransition<T, E>>; N]; N],
+    // Destinations per source by descending priority, then in the order they were added
+    transition_order: [[usize; N]; N],
+    num_transitions: [usize; N],
+    active_state: Option<usize>,
+    active_state_initialized: bool,
+}
+
+impl<T, const N: usize, E> FixedStateMachine<T, N, E> {
+    pub fn new(data: T) -> FixedStateMachine<T, N, E> {
+        FixedStateMachine {
+            data,
+            states: core::array::from_fn(|_| None),
+            num_states: 0,
+            transitions: core::array::from_fn(|_| core::array::from_fn(|_| None)),
+            transition_order: [[0; N]; N],
+            num_transitions: [0; N],
+            active_state: None,
+            active_state_initialized: false,
+        }
+    }
+
+    pub fn data(&self) -> &T {
+        &self.data
+    }
+
+    pub fn data_mut(&mut self) -> &mut T {
+        &mut self.data
+    }
+
+    pub fn current_state_index(&self) -> Option<usize> {
+        self.active_state
+    }
+
+    pub fn state(&self, index: usize) -> Result<&FixedState<T, E>, FsmError> {
+        if index >= self.num_states {
+            Err(FsmError::StateIndexOutOfBounds)
+        } else {
+            self.states[index].as_ref().ok_or(FsmError::StateIsEmpty)
+        }
+    }
+
+    pub fn transition(&self, src: usize, dst: usize) -> Result<&FixedTransition<T, E>, FsmError> {
+        if src >= self.num_states || dst >= self.num_states {
+            Err(FsmError::TransitionIndexOutOfBounds)
+        } else {
+            self.transitions[src][dst].as_ref().ok_or(FsmError::TransitionIsEmpty)
+        }
+    }
+
+    pub fn add_state(&mut self, s: FixedState<T, E>) -> Result<usize, FsmError> {
+        if self.num_states >= N {
+            return Err(FsmError::MaxNumberOfStatesExceeded);
+        }
+        let index = self.num_states;
+        self.states[index] = Some(s);
+        self.num_states += 1;
+        Ok(index)
+    }
+
+    pub fn add_transition(&mut self, t: FixedTransition<T, E>, src: usize, dst: usize) -> Result<(), FsmError> {
+        if src >= self.num_states || dst >= self.num_states {
+            return Err(FsmError::TransitionIndexOutOfBounds);
+        }
+        if src == dst {
+            return Err(FsmError::AddTransitionSrcDstStatesEqual);
+        }
+        if t.src != src || t.dst != dst {
+            return Err(FsmError::TransitionEndpointsMismatch);
+        }
+
+        let priority = t.priority;
+        let order = &mut self.transition_order[src];
+        let len = &mut self.num_transitions[src];
+        if self.transitions[src][dst].is_some() {
+            // A replaced transition is reinserted by its new priority
+            let position = order[..*len].iter().position(|&index| index == dst).expect("Failed to acquire transition order");
+            order.copy_within(position + 1..*len, position);
+            *len -= 1;
+        }
+        self.transitions[src][dst] = Some(t);
+
+        // Insert after every transition of equal or higher priority
+        let transitions = &self.transitions[src];
+        let position = order[..*len].iter()
+            .position(|&index| transitions[index].as_ref().is_some_and(|transition| transition.priority < priority))
+            .unwrap_or(*len);
+        order.copy_within(position..*len, position + 1);
+        order[position] = dst;
+        *len += 1;
+        Ok(())
+    }
+
+    pub fn set_active_state(&mut self, index: usize) -> Result<(), FsmError> {
+        self.state(index)?;
+        self.active_state = Some(index);
+        Ok(())
+    }
+
+    pub fn run(&mut self) -> FixedOutcome<E> {
+        let active_state_index = match self.active_state {
+            Some(index) => index,
+            None => return FixedOutcome::Errored(FsmError::NoActiveState.into()),
+        };
+        let active_state = self.states[active_state_index].as_ref().expect("Failed to acquire active state");
+
+        if !self.active_state_initialized {
+            if let Err(e) = (active_state.init)(&mut self.data) {
+                return FixedOutcome::Errored(FsmErrorKind::User(e));
+            }
+            self.active_state_initialized = true;
+        }
+        if let Err(e) = (active_state.exec)(&mut self.data) {
+            return FixedOutcome::Errored(FsmErrorKind::User(e));
+        }
+
+        let transitions = &self.transitions[active_state_index];
+        let next = self.transition_order[active_state_index][..self.num_transitions[active_state_index]].iter()
+            .copied()
+            .find(|&dst| transitions[dst].as_ref().is_some_and(|transition| (transition.check)(&self.data)));
+        let next_state_index = match next {
+            Some(dst) => dst,
+            None => return FixedOutcome::Stayed(active_state_index),
+        };
+
+        let transition = transitions[next_state_index].as_ref().expect("Failed to acquire transition");
+        if let Err(e) = (transition.done)(&mut self.data) {
+            return FixedOutcome::Errored(FsmErrorKind::User(e));
+        }
+        if let Err(e) = (active_state.exit)(&mut self.data) {
+            return FixedOutcome::Errored(FsmErrorKind::User(e));
+        }
+
+        self.active_state = Some(next_state_index);
+        self.active_state_initialized = false;
+
+        FixedOutcome::Transitioned {
+            from: active_state_index,
+            to: next_state_index,
+            via: transition.name,
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use petgraph::graph::{Graph, NodeIndex};
+
+use crate::{FsmError, State, StateCallbacks, StateMachine, Transition, TransitionCallbacks};
+
+impl<T: Clone, E> StateMachine<T, E> {
+    // Node indices become state indices, callbacks are looked up by node and edge weight
+    pub fn from_petgraph(data: T,
+                         graph: &Graph<String, String>,
+                         states: impl Fn(&str) -> Option<StateCallbacks<T, E>>,
+                         transitions: impl Fn(&str) -> Option<TransitionCallbacks<T, E>>
+    ) -> Result<StateMachine<T, E>, FsmError> {
+        let mut sm = StateMachine::new(data, graph.node_count());
+
+        for name in graph.node_weights() {
+            let (init, exec) = states(name).ok_or(FsmError::UnresolvedName)?;
+            sm.add_state(State { init, exec, ..State::new(name.as_str(), State::noop_init, State::noop_exec) })?;
+        }
+
+        for edge in graph.raw_edges() {
+            let (src, dst) = (edge.source().index(), edge.target().index());
+            let (check, done) = transitions(&edge.weight).ok_or(FsmError::UnresolvedName)?;
+            let transition = Transition { check, done, ..Transition::new(edge.weight.as_str(), src, dst, Transition::never, crate::noop_done) };
+            sm.place_transition(transition)?;
+        }
+        Ok(sm)
+    }
+
+    // Removed states are left out, so node indices only match state indices when no state was removed
+    pub fn to_petgraph(&self) -> Graph<String, String> {
+        let mut graph = Graph::with_capacity(self.num_states, 0);
+        let mut nodes: Vec<Option<NodeIndex>> = vec![None; self.num_states];
+
+        for (index, state) in self.states_iter() {
+            nodes[index] = Some(graph.add_node(state.name.clone()));
+        }
+
+        for (src, dst, transition) in self.transitions_iter() {
+            if let (Some(src), Some(dst)) = (nodes[src], nodes[dst]) {
+                graph.add_edge(src, dst, transition.name.clone());
+            }
+        }
+        graph
+    }
+}
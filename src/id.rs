@@ -0,0 +1,46 @@
+use crate::StateMachine;
+
+// Typed handles for a state and a transition, so a state index can't be passed where a transition is expected.
+// Every StateMachine method taking a state also takes a plain usize index, for code that still passes indices around
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StateId(pub usize);
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TransitionId {
+    pub src: StateId,
+    pub dst: StateId,
+}
+
+impl TransitionId {
+    pub(crate) fn new(src: usize, dst: usize) -> TransitionId {
+        TransitionId { src: StateId(src), dst: StateId(dst) }
+    }
+}
+
+impl From<usize> for StateId {
+    fn from(index: usize) -> StateId {
+        StateId(index)
+    }
+}
+
+impl From<StateId> for usize {
+    fn from(id: StateId) -> usize {
+        id.0
+    }
+}
+
+impl From<TransitionId> for (usize, usize) {
+    fn from(id: TransitionId) -> (usize, usize) {
+        (id.src.0, id.dst.0)
+    }
+}
+
+impl<T: Clone, E> StateMachine<T, E> {
+    pub fn state_id_by_name(&self, name: &str) -> Option<StateId> {
+        self.state_by_name(name).map(StateId)
+    }
+
+    pub fn current_state_id(&self) -> Option<StateId> {
+        self.active_state.map(StateId)
+    }
+}
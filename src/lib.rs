@@ -1,7 +1,81 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+// Without alloc only the error types and the allocation-free machines are available
+#[cfg(feature = "alloc")]
 extern crate alloc;
 extern crate core;
 
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+#[cfg(feature = "alloc")]
+use alloc::rc::Rc;
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use alloc::vec;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(feature = "alloc")]
+use core::cell::RefCell;
 use core::fmt::{Display, Formatter};
+#[cfg(feature = "alloc")]
+use core::fmt::{Debug, Write};
+
+#[cfg(feature = "alloc")]
+mod analysis;
+#[cfg(feature = "async")]
+mod asynchronous;
+#[cfg(feature = "alloc")]
+mod builder;
+#[cfg(feature = "std")]
+mod clock;
+#[cfg(feature = "alloc")]
+mod debug;
+#[cfg(feature = "heapless")]
+mod embedded;
+#[cfg(feature = "alloc")]
+mod export;
+mod fixed;
+#[cfg(feature = "petgraph")]
+mod graph;
+#[cfg(feature = "alloc")]
+mod id;
+#[cfg(feature = "metrics")]
+mod metrics;
+#[cfg(feature = "rand")]
+mod random;
+#[cfg(feature = "alloc")]
+mod region;
+#[cfg(feature = "alloc")]
+mod snapshot;
+#[cfg(feature = "alloc")]
+mod storage;
+#[cfg(feature = "alloc")]
+mod submachine;
+#[cfg(feature = "alloc")]
+mod topology;
+#[cfg(feature = "alloc")]
+mod trace;
+
+#[cfg(feature = "async")]
+pub use asynchronous::{AsyncState, AsyncStateMachine, AsyncTransition, FsmFuture};
+#[cfg(feature = "alloc")]
+pub use builder::StateMachineBuilder;
+#[cfg(feature = "derive")]
+pub use call_fsm_derive::FsmStates;
+#[cfg(feature = "std")]
+pub use clock::{Clock, MockClock, SystemClock};
+#[cfg(feature = "heapless")]
+pub use embedded::{EmbeddedOutcome, EmbeddedState, EmbeddedStateMachine, EmbeddedTransition};
+pub use fixed::{FixedOutcome, FixedState, FixedStateMachine, FixedTransition};
+#[cfg(feature = "alloc")]
+pub use id::{StateId, TransitionId};
+#[cfg(feature = "alloc")]
+pub use snapshot::FsmSnapshot;
+#[cfg(feature = "alloc")]
+pub use topology::{FsmTopology, StateCallbacks, TransitionCallbacks};
+#[cfg(feature = "alloc")]
+pub use trace::TraceEntry;
 
 #[macro_export]
 macro_rules! declare_data_type {
@@ -10,17 +84,22 @@ macro_rules! declare_data_type {
     }
 }
 
+// With the data type given, no DataType alias is needed and machines of different types can share a scope
 #[macro_export]
 macro_rules! declare_state_machine {
     ($name:ident, $data: ident, $num_states:expr) => {
         let mut $name: StateMachine<DataType> = StateMachine::new($data, $num_states);
-    }
+    };
+    ($name:ident, $data: ident, $dt:ty, $num_states:expr) => {
+        let mut $name: $crate::StateMachine<$dt> = $crate::StateMachine::new($data, $num_states);
+    };
 }
 
+// States and transitions take their types from the machine they are added to
 #[macro_export]
 macro_rules! new_state {
     ($sm:ident, $name:ident, $init:expr, $exec:expr) => {
-        let $name: State<DataType> = State::new(
+        let $name = $crate::macro_state(&$sm,
             stringify!($name),
             $init,
             $exec);
@@ -31,19 +110,76 @@ macro_rules! new_state {
 #[macro_export]
 macro_rules! new_transition {
     ($sm:ident, $src:ident, $dst: ident, $check:expr, $done:expr) => {
-        let _t: Transition<DataType> = Transition::new(
+        let _t = $crate::macro_transition(&$sm,
             concat!(stringify!($src), "__", stringify!($dst)),
             $src,
             $dst,
             $check,
             $done);
-        $sm.add_transition(_t, $src, $dst).expect("Failed to add transition");
+        // Routed by the endpoints, so src and dst may be the same state
+        $sm.place_transition(_t).expect("Failed to add transition");
     }
 }
 
-pub type FsmResult = Result<(), FsmError>;
+// The whole machine in one block, states are bound by name so a typo in a transition fails to compile
+#[macro_export]
+macro_rules! fsm {
+    (data: $data:expr,
+     states { $($state:ident: ($init:expr, $exec:expr)),* $(,)? }
+     transitions { $($src:ident -> $dst:ident: ($check:expr, $done:expr)),* $(,)? }
+     $(initial: $initial:ident)?) => {
+        $crate::fsm!(data: $data, error: $crate::FsmError,
+                     states { $($state: ($init, $exec)),* }
+                     transitions { $($src -> $dst: ($check, $done)),* }
+                     $(initial: $initial)?)
+    };
+    (data: $data:expr, error: $error:ty,
+     states { $($state:ident: ($init:expr, $exec:expr)),* $(,)? }
+     transitions { $($src:ident -> $dst:ident: ($check:expr, $done:expr)),* $(,)? }
+     $(initial: $initial:ident)?) => {{
+        let mut sm: $crate::StateMachine<_, $error> = $crate::StateMachine::new($data, [$(stringify!($state)),*].len());
+        $(
+            let $state = $crate::macro_state(&sm, stringify!($state), $init, $exec);
+            let $state = sm.add_state($state).expect("Failed to add state");
+        )*
+        $(
+            let _t = $crate::macro_transition(&sm, concat!(stringify!($src), "__", stringify!($dst)), $src, $dst, $check, $done);
+            sm.place_transition(_t).expect("Failed to add transition");
+        )*
+        $(
+            sm.set_initial($initial).expect("Failed to set initial state");
+            sm.set_active_state($initial).expect("Failed to set initial state");
+        )?
+        sm
+    }};
+}
+
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+pub fn macro_state<T: Clone, E>(_sm: &StateMachine<T, E>,
+                                name: &str,
+                                init: impl Fn(&State<T, E>, &mut T) -> Result<(), E> + 'static,
+                                exec: impl Fn(&State<T, E>, &mut T) -> Result<(), E> + 'static
+) -> State<T, E> {
+    State::new(name, init, exec)
+}
+
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+pub fn macro_transition<T: Clone, E>(_sm: &StateMachine<T, E>,
+                                     name: &str,
+                                     src: impl Into<StateId>,
+                                     dst: impl Into<StateId>,
+                                     check: impl Fn(&Transition<T, E>, &T) -> bool + 'static,
+                                     done: impl Fn(&Transition<T, E>, &mut T) -> Result<(), E> + 'static
+) -> Transition<T, E> {
+    Transition::new(name, src, dst, check, done)
+}
+
+pub type FsmResult<E = FsmError> = Result<(), E>;
 
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum FsmError {
     StateIndexOutOfBounds,
     TransitionIndexOutOfBounds,
@@ -51,51 +187,403 @@ pub enum FsmError {
     AddTransitionSrcDstStatesEqual,
     StateIsEmpty,
     TransitionIsEmpty,
+    MaxStepsExceeded,
+    HistoryIsEmpty,
+    UnsupportedBySparseStorage,
+    DuplicateStateName,
+    TransitionEndpointsMismatch,
+    NoActiveState,
+    CallbackPanicked,
+    UnresolvedName,
+    StuckInState,
+    OutOfFuel,
+    RegionIndexOutOfBounds,
+    RegionOverlap,
+    StateNotInRegion,
+    TransitionLeavesRegion,
+    NameTooLong,
+    MaxNumberOfTransitionsExceeded,
+    CyclicGraph,
 }
 
 impl Display for FsmError {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{:?}", self)
     }
 }
 
-pub type StateCallback<T> = dyn Fn(&State<T>, &mut T) -> Result<(), FsmError>;
-pub type TransCheckCallback<T> = dyn Fn(&Transition<T>, &T) -> bool;
-pub type TransDoneCallback<T> = dyn Fn(&Transition<T>, &mut T) -> Result<(), FsmError>;
-pub type ErrorCallback<T> = dyn Fn(FsmError, &mut T) -> Option<Destination>;
+// Errors raised by the machine itself are structural, errors returned from callbacks are the user's own type
+#[derive(Clone, Debug, PartialEq)]
+pub enum FsmErrorKind<E = FsmError> {
+    Structural(FsmError),
+    User(E),
+}
+
+impl<E> From<FsmError> for FsmErrorKind<E> {
+    fn from(error: FsmError) -> FsmErrorKind<E> {
+        FsmErrorKind::Structural(error)
+    }
+}
+
+impl<E: Display> Display for FsmErrorKind<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            FsmErrorKind::Structural(error) => write!(f, "{}", error),
+            FsmErrorKind::User(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+pub type StateCallback<T, E = FsmError> = dyn Fn(&State<T, E>, &mut T) -> Result<(), E>;
+#[cfg(feature = "alloc")]
+pub type TransCheckCallback<T, E = FsmError> = dyn Fn(&Transition<T, E>, &T) -> bool;
+#[cfg(feature = "alloc")]
+pub type TransDoneCallback<T, E = FsmError> = dyn Fn(&Transition<T, E>, &mut T) -> Result<(), E>;
+#[cfg(feature = "alloc")]
+pub type ErrorCallback<T, E = FsmError> = dyn Fn(FsmErrorKind<E>, &mut T) -> Option<Destination>;
+#[cfg(feature = "alloc")]
+pub type PhaseErrorCallback<T, E = FsmError> = dyn Fn(FsmErrorKind<E>, Phase, &mut T) -> Option<Destination>;
+#[cfg(feature = "alloc")]
+pub type ContextErrorCallback<T, E = FsmError> = dyn Fn(FsmErrorKind<E>, ErrorContext, &mut T) -> Option<Destination>;
+#[cfg(feature = "alloc")]
+pub type TransStatesDoneCallback<T, E = FsmError> = dyn Fn(&Transition<T, E>, &State<T, E>, &State<T, E>, &mut T) -> Result<(), E>;
+#[cfg(feature = "alloc")]
+pub type TransReasonCallback<T, E = FsmError> = dyn Fn(&Transition<T, E>, &T) -> Result<(), &'static str>;
+#[cfg(feature = "alloc")]
+pub type TransitionObserver<T> = dyn Fn(usize, usize, &str, &T);
+#[cfg(feature = "alloc")]
+pub type BlockedObserver<T> = dyn Fn(usize, &str, &'static str, &T);
 
+#[cfg(feature = "alloc")]
+type ErrorCallbacks<T, E> = (Rc<ContextErrorCallback<T, E>>, Rc<ContextErrorCallback<T, E>>);
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Phase {
+    Init,
+    Exec,
+    TransitionDone,
+    Exit,
+    Guard,
+    Observer,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ErrorContext {
+    pub phase: Phase,
+    pub state: usize,
+    pub transition: Option<(usize, usize)>,
+    pub previous_state: Option<usize>,
+}
+
+#[cfg(feature = "alloc")]
 pub enum Destination {
     Index(usize),
     Name(String),
+    History,
+}
+
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, PartialEq)]
+pub enum StepOutcome<E = FsmError> {
+    Transitioned { from: usize, to: usize, via: String },
+    Stayed(usize),
+    Internal { state: usize, via: String },
+    Errored(FsmErrorKind<E>),
+    Halted,
+}
+
+// Runs any user callback, with catch-unwind a panic in it becomes CallbackPanicked
+#[cfg(feature = "alloc")]
+fn guarded<R>(callback: impl FnOnce() -> R) -> Result<R, FsmError> {
+    #[cfg(feature = "catch-unwind")]
+    {
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(callback)).map_err(|_| FsmError::CallbackPanicked)
+    }
+    #[cfg(not(feature = "catch-unwind"))]
+    {
+        Ok(callback())
+    }
+}
+
+#[cfg(feature = "alloc")]
+fn invoke<E>(callback: impl FnOnce() -> Result<(), E>) -> Result<(), FsmErrorKind<E>> {
+    guarded(callback)?.map_err(FsmErrorKind::User)
+}
+
+// Mutable access to one state, on drop a changed name is moved over in the name lookup.
+// A rename to a name another state already has is undone, rename_state reports it instead
+#[cfg(feature = "alloc")]
+pub struct StateMut<'a, T: 'static + Clone, E: 'static = FsmError> {
+    sm: &'a mut StateMachine<T, E>,
+    index: usize,
+    old_name: String,
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Clone, E> core::ops::Deref for StateMut<'_, T, E> {
+    type Target = State<T, E>;
+
+    fn deref(&self) -> &State<T, E> {
+        self.sm.states[self.index].as_ref().expect("Failed to acquire state")
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Clone, E> core::ops::DerefMut for StateMut<'_, T, E> {
+    fn deref_mut(&mut self) -> &mut State<T, E> {
+        self.sm.states[self.index].as_mut().expect("Failed to acquire state")
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Clone, E> Drop for StateMut<'_, T, E> {
+    fn drop(&mut self) {
+        let index = self.index;
+        let renamed = self.sm.states[index].as_ref().is_some_and(|state| state.name != self.old_name);
+        if !renamed {
+            return;
+        }
+
+        let new_name = &self.sm.states[index].as_ref().expect("Failed to acquire state").name;
+        if self.sm.states_iter().any(|(other, state)| other != index && state.name == *new_name) {
+            let state = self.sm.states[index].as_mut().expect("Failed to acquire state");
+            state.name = core::mem::take(&mut self.old_name);
+        } else {
+            #[cfg(feature = "std")]
+            {
+                let new_name = new_name.clone();
+                self.sm.state_names.remove(&self.old_name);
+                self.sm.state_names.insert(new_name, index);
+            }
+        }
+    }
+}
+
+// Mutable access to a stored transition, its endpoints are the keys it's stored under so changes to
+// them are undone on drop
+#[cfg(feature = "alloc")]
+pub struct TransitionMut<'a, T: 'static + Clone, E: 'static = FsmError> {
+    transition: &'a mut Transition<T, E>,
+    src: usize,
+    dst: usize,
 }
 
-pub struct StateMachine<T: 'static + Clone> {
+#[cfg(feature = "alloc")]
+impl<T: Clone, E> core::ops::Deref for TransitionMut<'_, T, E> {
+    type Target = Transition<T, E>;
+
+    fn deref(&self) -> &Transition<T, E> {
+        self.transition
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Clone, E> core::ops::DerefMut for TransitionMut<'_, T, E> {
+    fn deref_mut(&mut self) -> &mut Transition<T, E> {
+        self.transition
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Clone, E> Drop for TransitionMut<'_, T, E> {
+    fn drop(&mut self) {
+        self.transition.src = self.src;
+        self.transition.dst = self.dst;
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[derive(Clone, Copy)]
+enum Fired {
+    Internal(usize),
+    Outgoing(usize),
+    Global(usize),
+    Default,
+}
+
+#[cfg(feature = "alloc")]
+pub struct StateMachine<T: 'static + Clone, E: 'static = FsmError> {
     data: T,
 
-    states: Vec<Option<State<T>>>,
+    states: Vec<Option<State<T, E>>>,
     num_states: usize,
+    #[cfg(feature = "std")]
+    state_names: std::collections::HashMap<String, usize>,
 
-    transitions: Vec<Vec<Option<Transition<T>>>>,
+    transitions: storage::TransitionTable<T, E>,
+    default_transitions: Vec<Option<Transition<T, E>>>,
+    internal_transitions: Vec<Vec<Transition<T, E>>>,
+    global_transitions: Vec<Transition<T, E>>,
+    global_transitions_first: bool,
     active_state: Option<usize>,
     active_state_initialized: bool,
+    error_routed: bool,
+    transactional: bool,
+    retries: u32,
+    stuck_threshold: Option<u32>,
+    stuck_runs: u32,
+    fuel: Option<u64>,
+    history: Option<usize>,
+    #[cfg(feature = "std")]
+    clock: Rc<dyn Clock>,
+    #[cfg(feature = "std")]
+    entered_at: Option<std::time::Instant>,
+    initial_state: Option<usize>,
+    final_states: Vec<bool>,
+    visit_counts: Vec<u64>,
+
+    error: Option<ErrorCallbacks<T, E>>,
+    observer: Option<Rc<TransitionObserver<T>>>,
+    error_writer: Option<Rc<RefCell<Box<dyn Write>>>>,
+    blocked_observer: Option<Rc<BlockedObserver<T>>>,
+    trace: Option<trace::Recorder>,
+    regions: Vec<region::Region>,
+    #[cfg(feature = "rand")]
+    rng: Option<random::SharedRng>,
+    #[cfg(feature = "rand")]
+    random_selection: bool,
 
-    error: Option<(&'static ErrorCallback<T>, &'static ErrorCallback<T>)>,
+    #[cfg(feature = "metrics")]
+    metrics: metrics::Metrics,
 }
 
-impl<T: Clone> StateMachine<T> {
-    pub fn new(data: T, max_states: usize) -> StateMachine<T> {
+#[cfg(feature = "alloc")]
+impl<T: Clone, E> Clone for StateMachine<T, E> {
+    fn clone(&self) -> StateMachine<T, E> {
+        StateMachine {
+            data: self.data.clone(),
+            states: self.states.clone(),
+            num_states: self.num_states,
+            #[cfg(feature = "std")]
+            state_names: self.state_names.clone(),
+            transitions: self.transitions.clone(),
+            default_transitions: self.default_transitions.clone(),
+            internal_transitions: self.internal_transitions.clone(),
+            global_transitions: self.global_transitions.clone(),
+            global_transitions_first: self.global_transitions_first,
+            active_state: self.active_state,
+            active_state_initialized: self.active_state_initialized,
+            error_routed: self.error_routed,
+            transactional: self.transactional,
+            retries: self.retries,
+            stuck_threshold: self.stuck_threshold,
+            stuck_runs: self.stuck_runs,
+            fuel: self.fuel,
+            history: self.history,
+            #[cfg(feature = "std")]
+            clock: self.clock.clone(),
+            #[cfg(feature = "std")]
+            entered_at: self.entered_at,
+            initial_state: self.initial_state,
+            final_states: self.final_states.clone(),
+            visit_counts: self.visit_counts.clone(),
+            error: self.error.clone(),
+            observer: self.observer.clone(),
+            error_writer: self.error_writer.clone(),
+            blocked_observer: self.blocked_observer.clone(),
+            trace: self.trace.clone(),
+            regions: self.regions.clone(),
+            #[cfg(feature = "rand")]
+            rng: self.rng.clone(),
+            #[cfg(feature = "rand")]
+            random_selection: self.random_selection,
+            #[cfg(feature = "metrics")]
+            metrics: self.metrics.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Clone, E> StateMachine<T, E> {
+    pub fn new(data: T, max_states: usize) -> StateMachine<T, E> {
+        StateMachine::with_transition_table(data, max_states, storage::TransitionTable::dense(max_states))
+    }
+
+    // Stores transitions as per-state edge lists, for large machines with few transitions
+    pub fn new_sparse(data: T, max_states: usize) -> StateMachine<T, E> {
+        StateMachine::with_transition_table(data, max_states, storage::TransitionTable::sparse(max_states))
+    }
+
+    fn with_transition_table(data: T, max_states: usize, transitions: storage::TransitionTable<T, E>) -> StateMachine<T, E> {
         StateMachine {
             data,
-            states: vec![None; max_states],
+            states: (0..max_states).map(|_| None).collect(),
             num_states: 0,
-            transitions: vec![vec![None; max_states]; max_states],
+            #[cfg(feature = "std")]
+            state_names: std::collections::HashMap::new(),
+            transitions,
+            default_transitions: (0..max_states).map(|_| None).collect(),
+            internal_transitions: (0..max_states).map(|_| Vec::new()).collect(),
+            global_transitions: Vec::new(),
+            global_transitions_first: false,
             active_state: None,
             active_state_initialized: false,
-            error: None
+            error_routed: false,
+            transactional: false,
+            retries: 0,
+            stuck_threshold: None,
+            stuck_runs: 0,
+            fuel: None,
+            history: None,
+            #[cfg(feature = "std")]
+            clock: Rc::new(SystemClock),
+            #[cfg(feature = "std")]
+            entered_at: None,
+            initial_state: None,
+            final_states: vec![false; max_states],
+            visit_counts: vec![0; max_states],
+            error: None,
+            observer: None,
+            error_writer: None,
+            blocked_observer: None,
+            trace: None,
+            regions: Vec::new(),
+            #[cfg(feature = "rand")]
+            rng: None,
+            #[cfg(feature = "rand")]
+            random_selection: false,
+            #[cfg(feature = "metrics")]
+            metrics: metrics::Metrics::new(max_states),
         }
     }
 
-    pub fn state(&self, index: usize) -> Result<&State<T>, FsmError> {
+    pub fn data(&self) -> &T {
+        &self.data
+    }
+
+    pub fn data_mut(&mut self) -> &mut T {
+        &mut self.data
+    }
+
+    pub fn into_data(self) -> T {
+        self.data
+    }
+
+    pub fn replace_data(&mut self, data: T) -> T {
+        core::mem::replace(&mut self.data, data)
+    }
+
+    pub fn current_state_index(&self) -> Option<usize> {
+        self.active_state
+    }
+
+    pub fn current_state(&self) -> Option<&State<T, E>> {
+        self.active_state.and_then(|index| self.state(index).ok())
+    }
+
+    pub fn current_state_name(&self) -> Option<&str> {
+        self.current_state().map(|state| state.name.as_str())
+    }
+
+    pub fn is_in_state(&self, name: &str) -> bool {
+        self.current_state_name() == Some(name)
+    }
+
+    pub fn state(&self, index: impl Into<StateId>) -> Result<&State<T, E>, FsmError> {
+        let index = index.into().0;
         if index >= self.num_states {
             Err(FsmError::StateIndexOutOfBounds)
         } else if let Some(ref state) = self.states[index] {
@@ -105,71 +593,378 @@ impl<T: Clone> StateMachine<T> {
         }
     }
 
-    pub fn state_by_name(&self, name: String) -> Option<usize> {
-        for (i, s) in self.states.iter().enumerate() {
-            if let Some(state) = s {
-                if name == state.name {
-                    return Some(i);
-                }
-            }
+    pub fn contains_state(&self, index: impl Into<StateId>) -> bool {
+        let index = index.into().0;
+        index < self.num_states && self.states[index].is_some()
+    }
+
+    pub fn contains_transition(&self, src: impl Into<StateId>, dst: impl Into<StateId>) -> bool {
+        let src = src.into().0;
+        let dst = dst.into().0;
+        src < self.num_states && dst < self.num_states && self.transitions.get(src, dst).is_some()
+    }
+
+    pub fn state_by_name(&self, name: &str) -> Option<usize> {
+        #[cfg(feature = "std")]
+        {
+            self.state_names.get(name).copied()
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            self.states_iter().find(|(_, state)| state.name == name).map(|(index, _)| index)
         }
-        None
     }
 
-    pub fn mut_state(&mut self, index: usize) -> Result<&mut State<T>, FsmError> {
-        if index >= self.num_states {
-            Err(FsmError::StateIndexOutOfBounds)
-        } else if let Some(ref mut state) = self.states[index] {
-            Ok(state)
+    pub fn states_iter(&self) -> impl Iterator<Item = (usize, &State<T, E>)> {
+        self.states[..self.num_states].iter()
+            .enumerate()
+            .filter_map(|(index, s)| s.as_ref().map(|state| (index, state)))
+    }
+
+    pub fn transitions_iter(&self) -> impl Iterator<Item = (usize, usize, &Transition<T, E>)> {
+        self.transitions.iter(self.num_states)
+    }
+
+    // A rename through the returned guard is picked up by the name lookup when the guard drops
+    pub fn mut_state(&mut self, index: impl Into<StateId>) -> Result<StateMut<'_, T, E>, FsmError> {
+        let index = index.into().0;
+        let old_name = self.state(index)?.name.clone();
+        Ok(StateMut { sm: self, index, old_name })
+    }
+
+    pub fn transition(&self, src: impl Into<StateId>, dst: impl Into<StateId>) -> Result<&Transition<T, E>, FsmError> {
+        let src = src.into().0;
+        let dst = dst.into().0;
+        if src >= self.num_states || dst >= self.num_states {
+            Err(FsmError::TransitionIndexOutOfBounds)
+        } else if let Some(transition) = self.transitions.get(src, dst) {
+            Ok(transition)
         } else {
-            Err(FsmError::StateIsEmpty)
+            Err(FsmError::TransitionIsEmpty)
         }
     }
 
-    pub fn transition(&self, src: usize, dst: usize) -> Result<&Transition<T>, FsmError> {
+    // Coordinates of the first populated transition with this name
+    pub fn transition_by_name(&self, name: &str) -> Option<(usize, usize)> {
+        self.transitions_iter()
+            .find(|(_, _, transition)| transition.name == name)
+            .map(|(src, dst, _)| (src, dst))
+    }
+
+    // Evaluation order is fixed when a transition is added, change priorities through set_transition_priority
+    pub fn mut_transition(&mut self, src: impl Into<StateId>, dst: impl Into<StateId>) -> Result<TransitionMut<'_, T, E>, FsmError> {
+        let src = src.into().0;
+        let dst = dst.into().0;
         if src >= self.num_states || dst >= self.num_states {
             Err(FsmError::TransitionIndexOutOfBounds)
-        } else if let Some(ref transition) = self.transitions[src][dst] {
-            Ok(transition)
+        } else if let Some(transition) = self.transitions.get_mut(src, dst) {
+            Ok(TransitionMut { transition, src, dst })
         } else {
             Err(FsmError::TransitionIsEmpty)
         }
     }
 
-    pub fn active_transitions(&self, src: usize) -> Result<&[Option<Transition<T>>], FsmError> {
+    pub fn active_transitions(&self, src: impl Into<StateId>) -> Result<&[Option<Transition<T, E>>], FsmError> {
+        let src = src.into().0;
         if src >= self.num_states {
             Err(FsmError::TransitionIndexOutOfBounds)
         } else {
-            Ok(&self.transitions[src][..])
+            self.transitions.row(src).ok_or(FsmError::UnsupportedBySparseStorage)
+        }
+    }
+
+    // Outgoing transitions of the active state in evaluation order, guards are not evaluated
+    pub fn available_transitions(&self) -> Vec<(usize, &str)> {
+        match self.active_state {
+            Some(src) => self.transitions.outgoing(src)
+                .map(|(dst, transition)| (dst, transition.name.as_str()))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn enabled_transitions(&self) -> Vec<(usize, &str)> {
+        match self.active_state {
+            Some(src) => self.transitions.outgoing(src)
+                .filter(|(_, transition)| transition.enabled && guarded(|| transition.do_check(&self.data)).unwrap_or(false))
+                .map(|(dst, transition)| (dst, transition.name.as_str()))
+                .collect(),
+            None => Vec::new(),
         }
     }
 
-    pub fn add_state(&mut self, s: State<T>) -> Result<usize, FsmError> {
-        if self.num_states >= self.states.capacity() {
+    // A disabled transition stays in place but is skipped when the machine runs
+    pub fn set_transition_enabled(&mut self, src: impl Into<StateId>, dst: impl Into<StateId>, enabled: bool) -> FsmResult {
+        let src = src.into().0;
+        let dst = dst.into().0;
+        if src >= self.num_states || dst >= self.num_states {
+            return Err(FsmError::TransitionIndexOutOfBounds);
+        }
+        let transition = self.transitions.get_mut(src, dst).ok_or(FsmError::TransitionIsEmpty)?;
+        transition.enabled = enabled;
+        Ok(())
+    }
+
+    pub fn set_transition_callbacks(&mut self,
+                                    src: impl Into<StateId>,
+                                    dst: impl Into<StateId>,
+                                    check: impl Fn(&Transition<T, E>, &T) -> bool + 'static,
+                                    done: impl Fn(&Transition<T, E>, &mut T) -> Result<(), E> + 'static) -> FsmResult {
+        let src = src.into().0;
+        let dst = dst.into().0;
+        // The reason and state-aware done belong to the callbacks being replaced
+        let mut transition = self.mut_transition(src, dst)?;
+        transition.check = Rc::new(check);
+        transition.explain = None;
+        transition.done = Rc::new(done);
+        transition.done_between = None;
+        Ok(())
+    }
+
+    pub fn set_transition_priority(&mut self, src: impl Into<StateId>, dst: impl Into<StateId>, priority: i32) -> FsmResult {
+        let src = src.into().0;
+        let dst = dst.into().0;
+        self.mut_transition(src, dst)?.priority = priority;
+        self.transitions.sort(src);
+        Ok(())
+    }
+
+    pub fn add_state(&mut self, s: State<T, E>) -> Result<StateId, FsmError> {
+        if self.state_by_name(&s.name).is_some() {
+            Err(FsmError::DuplicateStateName)
+        } else if self.num_states >= self.states.len() {
             Err(FsmError::MaxNumberOfStatesExceeded)
         } else {
-            self.states[self.num_states] = Some(s);
             let index = self.num_states;
+            #[cfg(feature = "std")]
+            self.state_names.insert(s.name.clone(), index);
+            self.states[index] = Some(s);
             self.num_states += 1;
-            Ok(index)
+            Ok(StateId(index))
         }
     }
 
-    pub fn add_transition(&mut self, t: Transition<T>, src: usize, dst: usize) -> Result<(), FsmError>{
+    // Takes effect on the next run, an active state is not initialized again unless reinitialize_current is called
+    pub fn set_state_callbacks(&mut self,
+                               index: impl Into<StateId>,
+                               init: impl Fn(&State<T, E>, &mut T) -> Result<(), E> + 'static,
+                               exec: impl Fn(&State<T, E>, &mut T) -> Result<(), E> + 'static) -> FsmResult {
+        let index = index.into().0;
+        let mut state = self.mut_state(index)?;
+        state.init = Rc::new(init);
+        state.exec = Rc::new(exec);
+        Ok(())
+    }
+
+    pub fn rename_state(&mut self, index: impl Into<StateId>, new_name: impl Into<String>) -> FsmResult {
+        let index = index.into().0;
+        let new_name = new_name.into();
+        self.state(index)?;
+        if self.state_by_name(&new_name).is_some_and(|named| named != index) {
+            return Err(FsmError::DuplicateStateName);
+        }
+        self.mut_state(index)?.name = new_name;
+        Ok(())
+    }
+
+    // All states share the callbacks, nothing is added unless every state fits
+    pub fn add_states(&mut self,
+                      names: &[&str],
+                      init: impl Fn(&State<T, E>, &mut T) -> Result<(), E> + 'static,
+                      exec: impl Fn(&State<T, E>, &mut T) -> Result<(), E> + 'static) -> Result<Vec<StateId>, FsmError> {
+        for (position, name) in names.iter().enumerate() {
+            if self.state_by_name(name).is_some() || names[..position].contains(name) {
+                return Err(FsmError::DuplicateStateName);
+            }
+        }
+        if self.num_states + names.len() > self.states.len() {
+            return Err(FsmError::MaxNumberOfStatesExceeded);
+        }
+
+        let init: Rc<StateCallback<T, E>> = Rc::new(init);
+        let exec: Rc<StateCallback<T, E>> = Rc::new(exec);
+        names.iter()
+            .map(|&name| self.add_state(State { init: init.clone(), exec: exec.clone(), ..State::new(name, State::noop_init, State::noop_exec) }))
+            .collect()
+    }
+
+    pub fn remove_state(&mut self, index: impl Into<StateId>) -> FsmResult {
+        let index = index.into().0;
+        self.state(index)?;
+
+        // Keep the slot so the indices of the remaining states stay valid
+        #[cfg(feature = "std")]
+        if let Some(ref state) = self.states[index] {
+            self.state_names.remove(&state.name);
+        }
+        self.states[index] = None;
+        self.transitions.remove_state(index);
+        for default in self.default_transitions.iter_mut() {
+            if default.as_ref().is_some_and(|transition| transition.src == index || transition.dst == index) {
+                *default = None;
+            }
+        }
+        self.global_transitions.retain(|transition| transition.dst != index);
+        self.internal_transitions[index].clear();
+        self.regions.iter_mut().for_each(|region| region.remove_state(index));
+
+        if self.active_state == Some(index) {
+            self.set_active(None);
+            self.active_state_initialized = false;
+        }
+        if self.history == Some(index) {
+            self.history = None;
+        }
+        if self.initial_state == Some(index) {
+            self.initial_state = None;
+        }
+        self.final_states[index] = false;
+        Ok(())
+    }
+
+    pub fn add_transition(&mut self, t: Transition<T, E>, src: impl Into<StateId>, dst: impl Into<StateId>) -> Result<TransitionId, FsmError> {
+        let src = src.into().0;
+        let dst = dst.into().0;
         if src >= self.num_states || dst >= self.num_states {
             Err(FsmError::TransitionIndexOutOfBounds)
+        } else if !self.contains_state(src) || !self.contains_state(dst) {
+            Err(FsmError::StateIsEmpty)
         } else if src == dst {
             Err(FsmError::AddTransitionSrcDstStatesEqual)
+        } else if t.src != src || t.dst != dst {
+            Err(FsmError::TransitionEndpointsMismatch)
+        } else if self.leaves_region(src, dst) {
+            Err(FsmError::TransitionLeavesRegion)
+        } else {
+            self.insert_transition(t, src, dst);
+            Ok(TransitionId::new(src, dst))
+        }
+    }
+
+    // All transitions share the guard and action, the error carries the position of the first bad spec
+    // and nothing is added unless every spec is valid
+    pub fn add_transitions<S: Into<StateId> + Copy>(&mut self,
+                                                    specs: &[(S, S, &str)],
+                                                    check: impl Fn(&Transition<T, E>, &T) -> bool + 'static,
+                                                    done: impl Fn(&Transition<T, E>, &mut T) -> Result<(), E> + 'static) -> Result<(), (usize, FsmError)> {
+        let specs: Vec<(usize, usize, &str)> = specs.iter().map(|&(src, dst, name)| (src.into().0, dst.into().0, name)).collect();
+        if let Some(position) = specs.iter().position(|&(src, dst, _)| src >= self.num_states || dst >= self.num_states) {
+            return Err((position, FsmError::TransitionIndexOutOfBounds));
+        }
+        if let Some(position) = specs.iter().position(|&(src, dst, _)| !self.contains_state(src) || !self.contains_state(dst)) {
+            return Err((position, FsmError::StateIsEmpty));
+        }
+        if let Some(position) = specs.iter().position(|&(src, dst, _)| self.leaves_region(src, dst)) {
+            return Err((position, FsmError::TransitionLeavesRegion));
+        }
+
+        let check: Rc<TransCheckCallback<T, E>> = Rc::new(check);
+        let done: Rc<TransDoneCallback<T, E>> = Rc::new(done);
+        for (position, &(src, dst, name)) in specs.iter().enumerate() {
+            let t = Transition { check: check.clone(), done: done.clone(), ..Transition::new(name, src, dst, Transition::always, noop_done) };
+            self.place_transition(t).map_err(|e| (position, e))?;
+        }
+        Ok(())
+    }
+
+    // Places the transition by its own src and dst
+    pub fn place_transition(&mut self, t: Transition<T, E>) -> Result<TransitionId, FsmError> {
+        let (src, dst) = (t.src, t.dst);
+        if src == dst {
+            self.add_self_transition(t, src)
+        } else {
+            self.add_transition(t, src, dst)
+        }
+    }
+
+    pub fn add_self_transition(&mut self, t: Transition<T, E>, state: impl Into<StateId>) -> Result<TransitionId, FsmError> {
+        let state = state.into().0;
+        if state >= self.num_states {
+            Err(FsmError::TransitionIndexOutOfBounds)
+        } else if !self.contains_state(state) {
+            Err(FsmError::StateIsEmpty)
+        } else if t.src != state || t.dst != state {
+            Err(FsmError::TransitionEndpointsMismatch)
+        } else {
+            // Firing a self-transition re-enters the state, so its init runs again
+            self.insert_transition(t, state, state);
+            Ok(TransitionId::new(state, state))
+        }
+    }
+
+    // Runs the transition's done without leaving the state, so neither exit nor init run again
+    pub fn add_internal_transition(&mut self, t: Transition<T, E>, state: impl Into<StateId>) -> FsmResult {
+        let state = state.into().0;
+        if state >= self.num_states {
+            Err(FsmError::TransitionIndexOutOfBounds)
+        } else if !self.contains_state(state) {
+            Err(FsmError::StateIsEmpty)
+        } else if t.src != state || t.dst != state {
+            Err(FsmError::TransitionEndpointsMismatch)
         } else {
-            self.transitions[src][dst] = Some(t);
+            let internal = &mut self.internal_transitions[state];
+            internal.push(t);
+            internal.sort_by_key(|transition| core::cmp::Reverse(transition.priority));
             Ok(())
         }
     }
 
-    pub fn set_active_state(&mut self, s: usize) -> Result<(), FsmError> {
+    pub fn add_default_transition(&mut self, t: Transition<T, E>, src: impl Into<StateId>) -> FsmResult {
+        let src = src.into().0;
+        if src >= self.num_states || t.dst >= self.num_states {
+            Err(FsmError::TransitionIndexOutOfBounds)
+        } else if !self.contains_state(src) || !self.contains_state(t.dst) {
+            Err(FsmError::StateIsEmpty)
+        } else if t.src != src {
+            Err(FsmError::TransitionEndpointsMismatch)
+        } else if self.leaves_region(src, t.dst) {
+            Err(FsmError::TransitionLeavesRegion)
+        } else {
+            self.default_transitions[src] = Some(t);
+            Ok(())
+        }
+    }
+
+    // The source of a global transition is ignored, it fires from whichever state is active
+    pub fn add_global_transition(&mut self, t: Transition<T, E>, dst: impl Into<StateId>) -> FsmResult {
+        let dst = dst.into().0;
+        if dst >= self.num_states {
+            Err(FsmError::TransitionIndexOutOfBounds)
+        } else if !self.contains_state(dst) {
+            Err(FsmError::StateIsEmpty)
+        } else if t.dst != dst {
+            Err(FsmError::TransitionEndpointsMismatch)
+        } else if !self.regions.is_empty() {
+            // It would fire from every region, so it can't stay inside one
+            Err(FsmError::TransitionLeavesRegion)
+        } else {
+            self.global_transitions.push(t);
+            Ok(())
+        }
+    }
+
+    pub fn set_global_transitions_first(&mut self, first: bool) {
+        self.global_transitions_first = first;
+    }
+
+    pub fn remove_transition(&mut self, src: impl Into<StateId>, dst: impl Into<StateId>) -> FsmResult {
+        let src = src.into().0;
+        let dst = dst.into().0;
+        self.transition(src, dst)?;
+
+        self.transitions.remove(src, dst);
+        Ok(())
+    }
+
+    fn insert_transition(&mut self, t: Transition<T, E>, src: usize, dst: usize) {
+        self.transitions.insert(src, dst, t);
+    }
+
+    pub fn set_active_state(&mut self, s: impl Into<StateId>) -> Result<(), FsmError> {
+        let s = s.into().0;
         match self.state(s) {
             Ok(_) => {
-                self.active_state = Some(s);
+                self.set_active(Some(s));
                 Ok(())
             },
             Err(e) => Err(e),
@@ -177,152 +972,866 @@ impl<T: Clone> StateMachine<T> {
 
     }
 
-    pub fn set_error_callbacks(&mut self, init: &'static ErrorCallback<T>, exec: &'static ErrorCallback<T>) {
-        self.error = Some((init, exec))
+    pub fn set_initial(&mut self, index: impl Into<StateId>) -> FsmResult {
+        let index = index.into().0;
+        self.state(index)?;
+        self.initial_state = Some(index);
+        Ok(())
+    }
+
+    pub fn reset(&mut self) {
+        self.set_active(self.initial_state);
+        self.active_state_initialized = false;
+        self.history = None;
+    }
+
+    // The next run() calls init of the active state again without leaving it
+    pub fn reinitialize_current(&mut self) {
+        self.active_state_initialized = false;
     }
 
-    pub fn run(&mut self) {
-        if let Some(active_state_index) = self.active_state {
-            let active_state = self.state(active_state_index).expect("Failed to acquire active state").to_owned();
+    pub fn is_current_initialized(&self) -> bool {
+        self.active_state_initialized
+    }
+
+    pub fn reset_with(&mut self, data: T) {
+        self.data = data;
+        self.reset();
+    }
 
-            // Initialize state if needed
-            if !&self.active_state_initialized {
-                if let Err(e) = active_state.do_init(&mut self.data) {
-                    self.do_error_callback(e);
-                    return;
+    pub fn mark_final(&mut self, index: impl Into<StateId>) -> FsmResult {
+        let index = index.into().0;
+        self.state(index)?;
+        self.final_states[index] = true;
+        Ok(())
+    }
+
+    pub fn is_final(&self, index: impl Into<StateId>) -> bool {
+        let index = index.into().0;
+        self.final_states.get(index).copied().unwrap_or(false)
+    }
+
+    pub fn set_active_state_by_name(&mut self, name: &str) -> FsmResult {
+        match self.state_by_name(name) {
+            Some(index) => self.set_active_state(index),
+            None => Err(FsmError::StateIsEmpty),
+        }
+    }
+
+    pub fn history(&self) -> Option<usize> {
+        self.history
+    }
+
+    // The state active before the last state change, the same state history transitions return to
+    pub fn previous_state(&self) -> Option<usize> {
+        self.history
+    }
+
+    pub fn transition_to_history(&mut self) -> FsmResult {
+        match self.history {
+            Some(index) => {
+                self.state(index)?;
+                self.set_active(Some(index));
+                self.active_state_initialized = false;
+                Ok(())
+            },
+            None => Err(FsmError::HistoryIsEmpty),
+        }
+    }
+
+    pub fn set_error_callbacks(&mut self,
+                               init: impl Fn(FsmErrorKind<E>, &mut T) -> Option<Destination> + 'static,
+                               exec: impl Fn(FsmErrorKind<E>, &mut T) -> Option<Destination> + 'static) {
+        self.error = Some((
+            Rc::new(move |error, _context, data: &mut T| init(error, data)),
+            Rc::new(move |error, _context, data: &mut T| exec(error, data)),
+        ))
+    }
+
+    pub fn set_error_callbacks_with_phase(&mut self,
+                                          init: impl Fn(FsmErrorKind<E>, Phase, &mut T) -> Option<Destination> + 'static,
+                                          exec: impl Fn(FsmErrorKind<E>, Phase, &mut T) -> Option<Destination> + 'static) {
+        self.error = Some((
+            Rc::new(move |error, context: ErrorContext, data: &mut T| init(error, context.phase, data)),
+            Rc::new(move |error, context: ErrorContext, data: &mut T| exec(error, context.phase, data)),
+        ))
+    }
+
+    pub fn set_error_callbacks_ctx(&mut self,
+                                   init: impl Fn(FsmErrorKind<E>, ErrorContext, &mut T) -> Option<Destination> + 'static,
+                                   exec: impl Fn(FsmErrorKind<E>, ErrorContext, &mut T) -> Option<Destination> + 'static) {
+        self.error = Some((Rc::new(init), Rc::new(exec)))
+    }
+
+    // Every step uses one unit of fuel, a machine without fuel set runs unlimited
+    pub fn set_fuel(&mut self, steps: u64) {
+        self.fuel = Some(steps);
+    }
+
+    pub fn refuel(&mut self, steps: u64) {
+        if let Some(ref mut fuel) = self.fuel {
+            *fuel = fuel.saturating_add(steps);
+        }
+    }
+
+    pub fn fuel(&self) -> Option<u64> {
+        self.fuel
+    }
+
+    pub fn set_stuck_threshold(&mut self, threshold: Option<u32>) {
+        self.stuck_threshold = threshold;
+    }
+
+    // When set, data is restored to its state before a transition's done if that done fails
+    pub fn set_transactional(&mut self, transactional: bool) {
+        self.transactional = transactional;
+    }
+
+    pub fn set_transition_observer(&mut self, observer: impl Fn(usize, usize, &str, &T) + 'static) {
+        self.observer = Some(Rc::new(observer))
+    }
+
+    // Called for every guard that explains why it blocked, when the active state stays put
+    pub fn set_blocked_observer(&mut self, observer: impl Fn(usize, &str, &'static str, &T) + 'static) {
+        self.blocked_observer = Some(Rc::new(observer))
+    }
+
+    // Every error is also written as an "Error state: ..." line, without a writer errors are silent
+    pub fn set_error_writer(&mut self, writer: Box<dyn Write>) {
+        self.error_writer = Some(Rc::new(RefCell::new(writer)))
+    }
+
+    fn set_active(&mut self, next: Option<usize>) {
+        #[cfg(feature = "std")]
+        let now = self.clock.now();
+        #[cfg(feature = "metrics")]
+        self.metrics.record_state_change(self.active_state, next, now);
+        if next.is_some() && self.active_state.is_some() {
+            self.history = self.active_state;
+        }
+        if let Some(index) = next {
+            self.visit_counts[index] += 1;
+        }
+        self.retries = 0;
+        self.stuck_runs = 0;
+        #[cfg(feature = "std")]
+        {
+            self.entered_at = next.map(|_| now);
+        }
+        self.active_state = next;
+    }
+
+    // Exec retries used since the active state was entered
+    pub fn retry_count(&self) -> u32 {
+        self.retries
+    }
+
+    pub fn visit_count(&self, index: impl Into<StateId>) -> u64 {
+        let index = index.into().0;
+        self.visit_counts.get(index).copied().unwrap_or(0)
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.visit_counts.iter_mut().for_each(|count| *count = 0);
+        #[cfg(feature = "metrics")]
+        self.metrics.reset(self.clock.now());
+    }
+
+    // Picks the transition the state would take, only guards are run
+    // A panicking guard stops the selection with CallbackPanicked, without sampling the first
+    // passing outgoing transition wins even in random mode and the RNG is left untouched
+    fn select(&self,
+              active_state_index: usize,
+              event: Option<&str>,
+              #[cfg_attr(not(feature = "rand"), allow(unused_variables))] sample: bool,
+    ) -> Result<Option<Fired>, FsmError> {
+        // Internal transitions are checked before any transition that would leave the state
+        for (index, transition) in self.internal_transitions[active_state_index].iter().enumerate() {
+            if self.can_fire(transition, event)? {
+                return Ok(Some(Fired::Internal(index)));
+            }
+        }
+
+        // Check transitions by descending priority, then in the order they were added
+        #[cfg(not(feature = "rand"))]
+        let check_outgoing = || -> Result<Option<Fired>, FsmError> {
+            for (dst, transition) in self.transitions.outgoing(active_state_index) {
+                if self.can_fire(transition, event)? {
+                    return Ok(Some(Fired::Outgoing(dst)));
+                }
+            }
+            Ok(None)
+        };
+        #[cfg(feature = "rand")]
+        let check_outgoing = || self.sample_outgoing(active_state_index, event, sample);
+
+        // Global transitions into the active state are skipped unless the state opted in to
+        // self-transitions by registering one
+        let reenter = self.transitions.get(active_state_index, active_state_index).is_some();
+        let check_global = || -> Result<Option<Fired>, FsmError> {
+            for (index, transition) in self.global_transitions.iter().enumerate() {
+                if (reenter || transition.dst != active_state_index) && self.can_fire(transition, event)? {
+                    return Ok(Some(Fired::Global(index)));
+                }
+            }
+            Ok(None)
+        };
+
+        let next = if self.global_transitions_first {
+            check_global()?.or(check_outgoing()?)
+        } else {
+            check_outgoing()?.or(check_global()?)
+        };
+
+        // The default transition is a catch-all, it fires without a guard when no other transition did
+        Ok(next.or_else(|| {
+            self.default_transitions[active_state_index].as_ref()
+                .filter(|transition| transition.enabled && transition.event.as_deref() == event)
+                .map(|_| Fired::Default)
+        }))
+    }
+
+    // The transition run() would take next and its destination, without running exec or done.
+    // In random mode this is the first passing transition, run() may sample a different one
+    pub fn peek_transition(&self) -> Option<(usize, &str)> {
+        let src = self.active_state?;
+        if self.final_states[src] {
+            return None;
+        }
+
+        let transition = match self.select(src, None, false).ok()?? {
+            Fired::Internal(index) => self.internal_transitions[src].get(index),
+            Fired::Outgoing(dst) => self.transitions.get(src, dst),
+            Fired::Global(index) => self.global_transitions.get(index),
+            Fired::Default => self.default_transitions[src].as_ref(),
+        }?;
+        Some((transition.dst, transition.name.as_str()))
+    }
+
+    fn report_blocked(&self, active_state_index: usize, event: Option<&str>) -> FsmResult {
+        if let Some(ref observer) = self.blocked_observer {
+            let candidates = self.transitions.outgoing(active_state_index).map(|(_, transition)| transition)
+                .filter(|transition| transition.enabled && transition.event.as_deref() == event);
+            for transition in candidates {
+                if let Some(reason) = guarded(|| transition.blocked_reason(&self.data))? {
+                    guarded(|| observer(active_state_index, &transition.name, reason, &self.data))?;
                 }
             }
+        }
+        Ok(())
+    }
+
+    // Only transitions bound to the fired event are considered, or event-less ones when polled
+    fn can_fire(&self, transition: &Transition<T, E>, event: Option<&str>) -> Result<bool, FsmError> {
+        Ok(transition.enabled
+            && transition.event.as_deref() == event
+            && (self.timed_out(transition) || guarded(|| transition.do_check(&self.data))?))
+    }
+
+    // Timeouts need a clock, without std they never elapse
+    fn timed_out(&self, transition: &Transition<T, E>) -> bool {
+        #[cfg(feature = "std")]
+        {
+            let now = self.clock.now();
+            transition.timeout.zip(self.entered_at).is_some_and(|(timeout, entered_at)| now - entered_at >= timeout)
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            let _ = transition;
+            false
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Clone, E: Clone + Debug> StateMachine<T, E> {
+    pub fn run(&mut self) -> StepOutcome<E> {
+        self.step(None)
+    }
+
+    pub fn fire_event(&mut self, name: &str) -> StepOutcome<E> {
+        self.step(Some(name))
+    }
 
-            self.active_state_initialized = true;
+    fn step(&mut self, event: Option<&str>) -> StepOutcome<E> {
+        match self.fuel {
+            Some(0) => return StepOutcome::Errored(FsmError::OutOfFuel.into()),
+            Some(ref mut fuel) => *fuel -= 1,
+            None => {},
+        }
+        self.step_once(event)
+    }
+
+    fn step_once(&mut self, event: Option<&str>) -> StepOutcome<E> {
+        let active_state_index = match self.active_state {
+            Some(index) => index,
+            // Most likely set_active_state was never called, or the active state was removed
+            None => return StepOutcome::Errored(FsmError::NoActiveState.into()),
+        };
+
+        // A final state only runs its entry once and then stops the machine
+        let is_final = self.final_states[active_state_index];
+        if is_final && self.active_state_initialized {
+            return StepOutcome::Halted;
+        }
+
+        let active_state = self.states[active_state_index].as_ref().expect("Failed to acquire active state");
+
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("fsm_step", state = %active_state.name, index = active_state_index).entered();
+
+        // Initialize state if needed
+        if !self.active_state_initialized {
+            if let Err(e) = invoke(|| active_state.do_init(&mut self.data)) {
+                let context = ErrorContext { phase: Phase::Init, state: active_state_index, transition: None, previous_state: self.history };
+                return self.fail(e, context);
+            }
+        }
+
+        self.active_state_initialized = true;
+
+        // A failing exec is re-run right away while the state has retries left since it was entered
+        let active_state = self.states[active_state_index].as_ref().expect("Failed to acquire active state");
+        while let Err(e) = invoke(|| active_state.do_exec(&mut self.data)) {
+            if self.retries < active_state.max_retries {
+                self.retries += 1;
+                continue;
+            }
+            let context = ErrorContext { phase: Phase::Exec, state: active_state_index, transition: None, previous_state: self.history };
+            return self.fail(e, context);
+        }
+
+        if is_final {
+            return StepOutcome::Halted;
+        }
+
+        let context = ErrorContext { phase: Phase::Guard, state: active_state_index, transition: None, previous_state: self.history };
+        let fired = match self.select(active_state_index, event, true) {
+            Ok(Some(fired)) => fired,
+            // No transition check returned true, stay in the same active state
+            Ok(None) => {
+                if let Err(e) = self.report_blocked(active_state_index, event) {
+                    return self.fail(e.into(), ErrorContext { phase: Phase::Observer, ..context });
+                }
+                return self.stay(active_state_index);
+            },
+            Err(e) => return self.fail(e.into(), context),
+        };
+
+        let transition = match fired {
+            Fired::Internal(index) => self.internal_transitions[active_state_index].get(index),
+            Fired::Outgoing(dst) => self.transitions.get(active_state_index, dst),
+            Fired::Global(index) => self.global_transitions.get(index),
+            Fired::Default => self.default_transitions[active_state_index].as_ref(),
+        }.expect("Failed to acquire transition");
+        // Outgoing transitions are stored by their endpoints, the others were validated when added
+        let next_state_index = match fired {
+            Fired::Outgoing(dst) => dst,
+            _ => transition.dst,
+        };
+        let src_state = self.states[active_state_index].as_ref().expect("Failed to acquire active state");
+        let dst_state = self.states[next_state_index].as_ref().expect("Failed to acquire next state");
+        let backup = self.transactional.then(|| self.data.clone());
+        if let Err(e) = invoke(|| transition.do_done_between(src_state, dst_state, &mut self.data)) {
+            if let Some(data) = backup {
+                self.data = data;
+            }
+            let context = ErrorContext {
+                phase: Phase::TransitionDone,
+                state: active_state_index,
+                transition: Some((active_state_index, next_state_index)),
+                previous_state: self.history,
+            };
+            return self.fail(e, context);
+        }
+        if let Fired::Internal(_) = fired {
+            return StepOutcome::Internal {
+                state: active_state_index,
+                via: transition.name.clone(),
+            };
+        }
+        let via = transition.name.clone();
+
+        let active_state = self.states[active_state_index].as_ref().expect("Failed to acquire active state");
+        if let Err(e) = invoke(|| active_state.do_exit(&mut self.data)) {
+            let context = ErrorContext {
+                phase: Phase::Exit,
+                state: active_state_index,
+                transition: Some((active_state_index, next_state_index)),
+                previous_state: self.history,
+            };
+            return self.fail(e, context);
+        }
 
-            if let Err(e) = active_state.do_exec(&mut self.data) {
-                self.do_error_callback(e);
-                return;
+        if let Some(ref observer) = self.observer {
+            if let Err(e) = guarded(|| observer(active_state_index, next_state_index, &via, &self.data)) {
+                let context = ErrorContext {
+                    phase: Phase::Observer,
+                    state: active_state_index,
+                    transition: Some((active_state_index, next_state_index)),
+                    previous_state: self.history,
+                };
+                return self.fail(e.into(), context);
             }
+        }
+        self.record(active_state_index, next_state_index, &via);
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(from = active_state_index, to = next_state_index, transition = %via, "transition fired");
+        #[cfg(feature = "defmt")]
+        defmt::debug!("transition fired {} -> {} ({})", active_state_index, next_state_index, via.as_str());
+
+        #[cfg(feature = "metrics")]
+        self.metrics.record_transition(active_state_index, next_state_index);
+
+        // Some transition check returned true, move to dst state
+        self.set_active(Some(next_state_index));
+        self.active_state_initialized = false;
+
+        StepOutcome::Transitioned {
+            from: active_state_index,
+            to: next_state_index,
+            via,
+        }
+    }
+
+    // Moves to dst without checking guards, through the transition's done and the source's on_exit when one exists
+    pub fn force_transition(&mut self, dst: impl Into<StateId>) -> Result<(), FsmErrorKind<E>> {
+        let dst = dst.into().0;
+        if dst >= self.num_states {
+            return Err(FsmError::TransitionIndexOutOfBounds.into());
+        }
+        self.state(dst)?;
+
+        if let Some(src) = self.active_state {
+            if let Some(transition) = self.transitions.get(src, dst) {
+                let context = ErrorContext { phase: Phase::TransitionDone, state: src, transition: Some((src, dst)), previous_state: self.history };
+                let src_state = self.states[src].as_ref().expect("Failed to acquire active state");
+                let dst_state = self.states[dst].as_ref().expect("Failed to acquire next state");
+                let backup = self.transactional.then(|| self.data.clone());
+                if let Err(e) = invoke(|| transition.do_done_between(src_state, dst_state, &mut self.data)) {
+                    if let Some(data) = backup {
+                        self.data = data;
+                    }
+                    self.do_error_callback(e.clone(), context);
+                    return Err(e);
+                }
+
+                let active_state = self.states[src].as_ref().expect("Failed to acquire active state");
+                if let Err(e) = invoke(|| active_state.do_exit(&mut self.data)) {
+                    self.do_error_callback(e.clone(), ErrorContext { phase: Phase::Exit, ..context });
+                    return Err(e);
+                }
 
-            let mut next_state_index = active_state_index;
-            let next_state_trans = self.active_transitions(active_state_index).expect("Failed to acquire active transitions");
-            let mut check = false;
-
-            // Check transitions
-            for t in next_state_trans {
-                if let Some(transition) = t {
-                    let transition = transition.to_owned();
-                    check = transition.do_check(&self.data);
-                    if check {
-                        next_state_index = transition.dst;
-                        match transition.do_done(&mut self.data) {
-                            Err(e) => {
-                                self.do_error_callback(e);
-                                return;
-                            },
-                            Ok(_) => break
-                        }
+                // Observers, the trace and metrics see a forced transition like a fired one
+                let via = transition.name.clone();
+                if let Some(ref observer) = self.observer {
+                    if let Err(e) = guarded(|| observer(src, dst, &via, &self.data)) {
+                        let e = FsmErrorKind::from(e);
+                        self.do_error_callback(e.clone(), ErrorContext { phase: Phase::Observer, ..context });
+                        return Err(e);
                     }
                 }
+                self.record(src, dst, &via);
+
+                #[cfg(feature = "metrics")]
+                self.metrics.record_transition(src, dst);
             }
+        }
+
+        self.set_active(Some(dst));
+        self.active_state_initialized = false;
+        Ok(())
+    }
+
+    pub fn run_steps(&mut self, n: usize) -> Vec<StepOutcome<E>> {
+        self.steps().take(n).collect()
+    }
 
-            if !check {
-                // No transition check returned true, stay in the same active state
-                return;
+    // One outcome per run, ends after the machine halts, when it can't step at all,
+    // or on an error the error callback did not route to a state
+    pub fn steps(&mut self) -> impl Iterator<Item = StepOutcome<E>> + '_ {
+        let mut stopped = false;
+        core::iter::from_fn(move || {
+            if stopped {
+                return None;
             }
+            let outcome = self.run();
+            stopped = match outcome {
+                StepOutcome::Halted => true,
+                // These are returned before the error callback runs, so nothing can route them
+                StepOutcome::Errored(FsmErrorKind::Structural(FsmError::NoActiveState | FsmError::OutOfFuel)) => true,
+                StepOutcome::Errored(_) => !self.error_routed,
+                _ => false,
+            };
+            Some(outcome)
+        })
+    }
 
-            // Some transition check returned true, move to dst state
-            self.active_state = Some(next_state_index);
-            self.active_state_initialized = false;
+    pub fn run_until_stable(&mut self, max_steps: usize) -> Result<usize, FsmErrorKind<E>> {
+        for step in 1..=max_steps {
+            match self.run() {
+                StepOutcome::Transitioned { .. } | StepOutcome::Internal { .. } => continue,
+                StepOutcome::Stayed(_) | StepOutcome::Halted => return Ok(step),
+                StepOutcome::Errored(e) => return Err(e),
+            }
         }
+        Err(FsmError::MaxStepsExceeded.into())
+    }
 
-        // for s in &mut self.states {
-        //     if let Some(state) = s {
-        //         state.do_init().unwrap();
-        //         state.do_exec().unwrap();
-        //     }
-        // }
-        // for trans_src in &self.transitions {
-        //     for trans_dst in trans_src {
-        //         if let Some(trans) = trans_dst {
-        //             trans.do_check();
-        //             trans.do_done().unwrap();
-        //         }
-        //     }
-        // }
-    }
-
-    fn do_error_callback(&mut self, error: FsmError) {
-        println!("Error state: {}", error);
-        if let Some((callback_init, callback_exec)) = self.error {
-            callback_init(error, &mut self.data);
-            if let Some(next_state) = callback_exec(error, &mut self.data) {
-                match next_state {
-                    Destination::Index(next_state_index) => {
-                        if next_state_index < self.num_states {
-                            self.active_state = Some(next_state_index);
-                            self.active_state_initialized = false;
-                        }
-                    },
-                    Destination::Name(next_state_name) => {
-                        if let Some(next_state_index) = self.state_by_name(next_state_name) {
-                            self.active_state = Some(next_state_index);
-                            self.active_state_initialized = false;
-                        }
+    // Flags a state that ran more than the stuck threshold times in a row without a transition
+    fn stay(&mut self, active_state_index: usize) -> StepOutcome<E> {
+        self.stuck_runs += 1;
+        match self.stuck_threshold {
+            Some(threshold) if self.stuck_runs > threshold => {
+                self.stuck_runs = 0;
+                let context = ErrorContext { phase: Phase::Exec, state: active_state_index, transition: None, previous_state: self.history };
+                self.fail(FsmError::StuckInState.into(), context)
+            },
+            _ => StepOutcome::Stayed(active_state_index),
+        }
+    }
+
+    fn fail(&mut self, error: FsmErrorKind<E>, context: ErrorContext) -> StepOutcome<E> {
+        self.do_error_callback(error.clone(), context);
+        StepOutcome::Errored(error)
+    }
+
+    fn do_error_callback(&mut self, error: FsmErrorKind<E>, context: ErrorContext) {
+        // defmt takes over from log when both are enabled, user errors are only required to be Debug
+        #[cfg(all(feature = "log", not(feature = "defmt")))]
+        log::error!("Error state: {:?} ({:?})", error, context);
+        #[cfg(feature = "defmt")]
+        match error {
+            FsmErrorKind::Structural(ref error) => defmt::error!("Error state: {} ({})", error, context),
+            FsmErrorKind::User(_) => defmt::error!("Error state: user error ({})", context),
+        }
+        if let Some(ref writer) = self.error_writer {
+            let _ = writeln!(writer.borrow_mut(), "Error state: {:?} ({:?})", error, context);
+        }
+        // The error is already being reported, so a panicking error callback only loses its routing
+        let next_state = match self.error {
+            Some((ref callback_init, ref callback_exec)) => {
+                let _ = guarded(|| callback_init(error.clone(), context, &mut self.data));
+                guarded(|| callback_exec(error, context, &mut self.data)).ok().flatten()
+            },
+            None => None,
+        };
+
+        self.error_routed = false;
+        if let Some(next_state) = next_state {
+            match next_state {
+                Destination::Index(next_state_index) => {
+                    if self.contains_state(next_state_index) && !self.routes_out_of_region(next_state_index) {
+                        self.set_active(Some(next_state_index));
+                        self.active_state_initialized = false;
+                        self.error_routed = true;
+                    }
+                },
+                Destination::Name(next_state_name) => {
+                    if let Some(next_state_index) = self.state_by_name(&next_state_name).filter(|&index| !self.routes_out_of_region(index)) {
+                        self.set_active(Some(next_state_index));
+                        self.active_state_initialized = false;
+                        self.error_routed = true;
                     }
+                },
+                Destination::History => {
+                    self.error_routed = self.transition_to_history().is_ok();
                 }
             }
         }
     }
 }
 
-#[derive(Clone)]
-pub struct State<T: 'static> {
+// Callbacks are shared so that cloning a machine doesn't require cloning closures
+#[cfg(feature = "alloc")]
+pub struct State<T: 'static, E: 'static = FsmError> {
     pub name: String,
-    pub init: &'static StateCallback<T>,
-    pub exec: &'static StateCallback<T>,
+    pub init: Rc<StateCallback<T, E>>,
+    pub exec: Rc<StateCallback<T, E>>,
+    pub on_exit: Option<Rc<StateCallback<T, E>>>,
+    pub max_retries: u32,
+}
+
+#[cfg(feature = "alloc")]
+impl<T, E> Clone for State<T, E> {
+    fn clone(&self) -> State<T, E> {
+        State {
+            name: self.name.clone(),
+            init: self.init.clone(),
+            exec: self.exec.clone(),
+            on_exit: self.on_exit.clone(),
+            max_retries: self.max_retries,
+        }
+    }
 }
 
-impl<T> State<T> {
+#[cfg(feature = "alloc")]
+impl<T, E> State<T, E> {
     pub fn new<'b>(name: impl Into<alloc::borrow::Cow<'b, str>>,
-                   init: &'static StateCallback<T>,
-                   exec: &'static StateCallback<T>
-    ) -> State<T> {
-        State { name: name.into().into_owned(), init, exec }
+                   init: impl Fn(&State<T, E>, &mut T) -> Result<(), E> + 'static,
+                   exec: impl Fn(&State<T, E>, &mut T) -> Result<(), E> + 'static
+    ) -> State<T, E> {
+        State { name: name.into().into_owned(), init: Rc::new(init), exec: Rc::new(exec), on_exit: None, max_retries: 0 }
     }
 
-    pub fn do_init(&self, data: &mut T) -> Result<(), FsmError> {
+    pub fn with_exit<'b>(name: impl Into<alloc::borrow::Cow<'b, str>>,
+                         init: impl Fn(&State<T, E>, &mut T) -> Result<(), E> + 'static,
+                         exec: impl Fn(&State<T, E>, &mut T) -> Result<(), E> + 'static,
+                         on_exit: impl Fn(&State<T, E>, &mut T) -> Result<(), E> + 'static
+    ) -> State<T, E> {
+        State { on_exit: Some(Rc::new(on_exit)), ..State::new(name, init, exec) }
+    }
+
+    pub fn exec_only<'b>(name: impl Into<alloc::borrow::Cow<'b, str>>,
+                         exec: impl Fn(&State<T, E>, &mut T) -> Result<(), E> + 'static
+    ) -> State<T, E> {
+        State::new(name, State::noop_init, exec)
+    }
+
+    pub fn noop_init(_state: &State<T, E>, _data: &mut T) -> Result<(), E> {
+        Ok(())
+    }
+
+    pub fn noop_exec(_state: &State<T, E>, _data: &mut T) -> Result<(), E> {
+        Ok(())
+    }
+
+    // Callbacks that keep their own state between calls, clones of the state share it
+    pub fn new_mut<'b>(name: impl Into<alloc::borrow::Cow<'b, str>>,
+                       init: impl FnMut(&State<T, E>, &mut T) -> Result<(), E> + 'static,
+                       exec: impl FnMut(&State<T, E>, &mut T) -> Result<(), E> + 'static
+    ) -> State<T, E> {
+        let init = core::cell::RefCell::new(init);
+        let exec = core::cell::RefCell::new(exec);
+        State::new(name,
+                   move |state: &State<T, E>, data: &mut T| (init.borrow_mut())(state, data),
+                   move |state: &State<T, E>, data: &mut T| (exec.borrow_mut())(state, data))
+    }
+
+    pub fn do_init(&self, data: &mut T) -> Result<(), E> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::trace_span!("init", state = %self.name).entered();
         (self.init)(self, data)
     }
 
-    pub fn do_exec(&self, data: &mut T) -> Result<(), FsmError> {
+    pub fn do_exec(&self, data: &mut T) -> Result<(), E> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::trace_span!("exec", state = %self.name).entered();
         (self.exec)(self, data)
     }
+
+    pub fn do_exit(&self, data: &mut T) -> Result<(), E> {
+        match self.on_exit {
+            Some(ref on_exit) => on_exit(self, data),
+            None => Ok(()),
+        }
+    }
 }
 
-#[derive(Clone)]
-pub struct Transition<T: 'static + Clone> {
+#[cfg(feature = "alloc")]
+pub struct Transition<T: 'static + Clone, E: 'static = FsmError> {
     pub name: String,
     pub src: usize,
     pub dst: usize,
-    pub check: &'static TransCheckCallback<T>,
-    pub done: &'static TransDoneCallback<T>,
+    pub priority: i32,
+    pub weight: f64,
+    pub event: Option<String>,
+    pub timeout: Option<core::time::Duration>,
+    pub enabled: bool,
+    pub check: Rc<TransCheckCallback<T, E>>,
+    pub explain: Option<Rc<TransReasonCallback<T, E>>>,
+    pub done: Rc<TransDoneCallback<T, E>>,
+    done_between: Option<Rc<TransStatesDoneCallback<T, E>>>,
 }
 
-impl<T: Clone> Transition<T> {
+#[cfg(feature = "alloc")]
+impl<T: Clone, E> Clone for Transition<T, E> {
+    fn clone(&self) -> Transition<T, E> {
+        Transition {
+            name: self.name.clone(),
+            src: self.src,
+            dst: self.dst,
+            priority: self.priority,
+            weight: self.weight,
+            event: self.event.clone(),
+            timeout: self.timeout,
+            enabled: self.enabled,
+            check: self.check.clone(),
+            explain: self.explain.clone(),
+            done: self.done.clone(),
+            done_between: self.done_between.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Clone, E> Transition<T, E> {
     pub fn new<'b>(name: impl Into<alloc::borrow::Cow<'b, str>>,
-                   src: usize,
-                   dst: usize,
-                   check: &'static TransCheckCallback<T>,
-                   done: &'static TransDoneCallback<T>
-    ) -> Transition<T> {
+                   src: impl Into<StateId>,
+                   dst: impl Into<StateId>,
+                   check: impl Fn(&Transition<T, E>, &T) -> bool + 'static,
+                   done: impl Fn(&Transition<T, E>, &mut T) -> Result<(), E> + 'static
+    ) -> Transition<T, E> {
+        Transition::with_priority(name, src, dst, 0, check, done)
+    }
+
+    pub fn with_priority<'b>(name: impl Into<alloc::borrow::Cow<'b, str>>,
+                             src: impl Into<StateId>,
+                             dst: impl Into<StateId>,
+                             priority: i32,
+                             check: impl Fn(&Transition<T, E>, &T) -> bool + 'static,
+                             done: impl Fn(&Transition<T, E>, &mut T) -> Result<(), E> + 'static
+    ) -> Transition<T, E> {
         Transition {
             name: name.into().into_owned(),
-            src, dst,
-            check, done }
+            src: src.into().0,
+            dst: dst.into().0,
+            priority,
+            weight: 1.0,
+            event: None,
+            timeout: None,
+            enabled: true,
+            check: Rc::new(check),
+            explain: None,
+            done: Rc::new(done),
+            done_between: None }
+    }
+
+    pub fn always(_transition: &Transition<T, E>, _data: &T) -> bool {
+        true
+    }
+
+    pub fn never(_transition: &Transition<T, E>, _data: &T) -> bool {
+        false
+    }
+
+    // A done action that keeps its own state between calls, the guard stays side-effect free
+    pub fn new_mut<'b>(name: impl Into<alloc::borrow::Cow<'b, str>>,
+                       src: impl Into<StateId>,
+                       dst: impl Into<StateId>,
+                       check: impl Fn(&Transition<T, E>, &T) -> bool + 'static,
+                       done: impl FnMut(&Transition<T, E>, &mut T) -> Result<(), E> + 'static
+    ) -> Transition<T, E> {
+        let done = core::cell::RefCell::new(done);
+        Transition::new(name, src, dst, check, move |transition: &Transition<T, E>, data: &mut T| {
+            (done.borrow_mut())(transition, data)
+        })
+    }
+
+    // Fires once the source state has been active for the timeout, or earlier when the check passes
+    pub fn with_timeout<'b>(name: impl Into<alloc::borrow::Cow<'b, str>>,
+                            src: impl Into<StateId>,
+                            dst: impl Into<StateId>,
+                            timeout: core::time::Duration,
+                            check: impl Fn(&Transition<T, E>, &T) -> bool + 'static,
+                            done: impl Fn(&Transition<T, E>, &mut T) -> Result<(), E> + 'static
+    ) -> Transition<T, E> {
+        Transition {
+            timeout: Some(timeout),
+            ..Transition::new(name, src, dst, check, done)
+        }
+    }
+
+    pub fn on_event<'b, 'e>(name: impl Into<alloc::borrow::Cow<'b, str>>,
+                            src: impl Into<StateId>,
+                            dst: impl Into<StateId>,
+                            event: impl Into<alloc::borrow::Cow<'e, str>>,
+                            check: impl Fn(&Transition<T, E>, &T) -> bool + 'static,
+                            done: impl Fn(&Transition<T, E>, &mut T) -> Result<(), E> + 'static
+    ) -> Transition<T, E> {
+        Transition {
+            event: Some(event.into().into_owned()),
+            ..Transition::new(name, src, dst, check, done)
+        }
+    }
+
+    // The done action is also handed the source and destination states
+    pub fn with_states<'b>(name: impl Into<alloc::borrow::Cow<'b, str>>,
+                           src: impl Into<StateId>,
+                           dst: impl Into<StateId>,
+                           check: impl Fn(&Transition<T, E>, &T) -> bool + 'static,
+                           done: impl Fn(&Transition<T, E>, &State<T, E>, &State<T, E>, &mut T) -> Result<(), E> + 'static
+    ) -> Transition<T, E> {
+        Transition {
+            done_between: Some(Rc::new(done)),
+            ..Transition::new(name, src, dst, check, noop_done)
+        }
+    }
+
+    // The guard passes with Ok, or names the reason it blocked so it can be reported
+    pub fn with_reason<'b>(name: impl Into<alloc::borrow::Cow<'b, str>>,
+                           src: impl Into<StateId>,
+                           dst: impl Into<StateId>,
+                           guard: impl Fn(&Transition<T, E>, &T) -> Result<(), &'static str> + 'static,
+                           done: impl Fn(&Transition<T, E>, &mut T) -> Result<(), E> + 'static
+    ) -> Transition<T, E> {
+        Transition {
+            explain: Some(Rc::new(guard)),
+            ..Transition::new(name, src, dst, |transition: &Transition<T, E>, data: &T| transition.blocked_reason(data).is_none(), done)
+        }
+    }
+
+    pub fn blocked_reason(&self, data: &T) -> Option<&'static str> {
+        self.explain.as_ref().and_then(|explain| explain(self, data).err())
     }
 
     pub fn do_check(&self, data: &T) -> bool {
         (self.check)(self, data)
     }
 
-    pub fn do_done(&self, data: &mut T) -> Result<(), FsmError> {
+    pub fn do_done(&self, data: &mut T) -> Result<(), E> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::trace_span!("done", transition = %self.name, src = self.src, dst = self.dst).entered();
         (self.done)(self, data)
     }
-}
\ No newline at end of file
+
+    pub fn done_between(&self) -> Option<&TransStatesDoneCallback<T, E>> {
+        self.done_between.as_deref()
+    }
+
+    pub fn do_done_between(&self, src: &State<T, E>, dst: &State<T, E>, data: &mut T) -> Result<(), E> {
+        match self.done_between {
+            Some(ref done) => {
+                #[cfg(feature = "tracing")]
+                let _span = tracing::trace_span!("done", transition = %self.name, src = self.src, dst = self.dst).entered();
+                done(self, src, dst, data)
+            },
+            None => self.do_done(data),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+pub fn always<T: Clone, E>(_transition: &Transition<T, E>, _data: &T) -> bool {
+    true
+}
+
+#[cfg(feature = "alloc")]
+pub fn never<T: Clone, E>(_transition: &Transition<T, E>, _data: &T) -> bool {
+    false
+}
+
+#[cfg(feature = "alloc")]
+pub fn noop_done<T: Clone, E>(_transition: &Transition<T, E>, _data: &mut T) -> Result<(), E> {
+    Ok(())
+}
+
+#[cfg(feature = "alloc")]
+pub fn and<T: Clone, E>(a: impl Fn(&Transition<T, E>, &T) -> bool,
+                        b: impl Fn(&Transition<T, E>, &T) -> bool
+) -> impl Fn(&Transition<T, E>, &T) -> bool {
+    move |transition, data| a(transition, data) && b(transition, data)
+}
+
+#[cfg(feature = "alloc")]
+pub fn or<T: Clone, E>(a: impl Fn(&Transition<T, E>, &T) -> bool,
+                       b: impl Fn(&Transition<T, E>, &T) -> bool
+) -> impl Fn(&Transition<T, E>, &T) -> bool {
+    move |transition, data| a(transition, data) || b(transition, data)
+}
+
+#[cfg(feature = "alloc")]
+pub fn not<T: Clone, E>(a: impl Fn(&Transition<T, E>, &T) -> bool) -> impl Fn(&Transition<T, E>, &T) -> bool {
+    move |transition, data| !a(transition, data)
+}
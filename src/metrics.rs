@@ -0,0 +1,74 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use std::time::{Duration, Instant};
+
+use crate::{StateId, StateMachine};
+
+#[derive(Clone)]
+pub(crate) struct Metrics {
+    transition_counts: Vec<Vec<u64>>,
+    time_in_state: Vec<Duration>,
+    entered_at: Option<Instant>,
+}
+
+impl Metrics {
+    pub(crate) fn new(max_states: usize) -> Metrics {
+        Metrics {
+            transition_counts: vec![vec![0; max_states]; max_states],
+            time_in_state: vec![Duration::ZERO; max_states],
+            entered_at: None,
+        }
+    }
+
+    pub(crate) fn record_transition(&mut self, src: usize, dst: usize) {
+        self.transition_counts[src][dst] += 1;
+    }
+
+    pub(crate) fn record_state_change(&mut self, prev: Option<usize>, next: Option<usize>, now: Instant) {
+        if let (Some(prev), Some(entered_at)) = (prev, self.entered_at) {
+            self.time_in_state[prev] += now - entered_at;
+        }
+        self.entered_at = next.map(|_| now);
+    }
+
+    // Regions keep their own entry time, the active state's is swapped in and out with them
+    pub(crate) fn swap_entered_at(&mut self, entered_at: &mut Option<Instant>) {
+        core::mem::swap(&mut self.entered_at, entered_at);
+    }
+
+    pub(crate) fn reset(&mut self, now: Instant) {
+        self.transition_counts.iter_mut().flatten().for_each(|count| *count = 0);
+        self.time_in_state.iter_mut().for_each(|time| *time = Duration::ZERO);
+        self.restart(now);
+    }
+
+    pub(crate) fn restart(&mut self, now: Instant) {
+        if self.entered_at.is_some() {
+            self.entered_at = Some(now);
+        }
+    }
+}
+
+impl<T: Clone, E> StateMachine<T, E> {
+    pub fn transition_count(&self, src: impl Into<StateId>, dst: impl Into<StateId>) -> u64 {
+        let src = src.into().0;
+        let dst = dst.into().0;
+        self.metrics.transition_counts.get(src)
+            .and_then(|row| row.get(dst))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    pub fn time_in_state(&self, index: impl Into<StateId>) -> Duration {
+        let index = index.into().0;
+        let mut time = self.metrics.time_in_state.get(index).copied().unwrap_or(Duration::ZERO);
+
+        // Include the time spent in the active state so far
+        if let (Some(active), Some(entered_at)) = (self.active_state, self.metrics.entered_at) {
+            if active == index {
+                time += self.clock.now() - entered_at;
+            }
+        }
+        time
+    }
+}
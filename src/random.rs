@@ -0,0 +1,70 @@
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+
+use rand_core::RngCore;
+
+use crate::{Fired, FsmError, StateMachine};
+
+pub(crate) type SharedRng = Rc<RefCell<dyn RngCore>>;
+
+impl<T: Clone, E> StateMachine<T, E> {
+    // Seed the generator to make random runs reproducible, clones of the machine share it
+    pub fn set_rng(&mut self, rng: impl RngCore + 'static) {
+        self.rng = Some(Rc::new(RefCell::new(rng)));
+    }
+
+    // When on, a random passing outgoing transition fires, weighted by transition weight
+    pub fn set_random_selection(&mut self, random: bool) {
+        self.random_selection = random;
+    }
+
+    // The outgoing transitions whose guards pass from the active state, with their weights,
+    // these are what a random run samples from
+    pub fn candidate_transitions(&self) -> Vec<(usize, &str, f64)> {
+        let src = match self.active_state {
+            Some(src) => src,
+            None => return Vec::new(),
+        };
+        self.transitions.outgoing(src)
+            .filter(|(_, transition)| self.can_fire(transition, None).unwrap_or(false))
+            .map(|(dst, transition)| (dst, transition.name.as_str(), transition.weight))
+            .collect()
+    }
+
+    // Falls back to the first passing transition without a generator or positive weights
+    pub(crate) fn sample_outgoing(&self, src: usize, event: Option<&str>, sample: bool) -> Result<Option<Fired>, FsmError> {
+        let rng = match self.rng {
+            Some(ref rng) if sample && self.random_selection => rng,
+            _ => {
+                for (dst, transition) in self.transitions.outgoing(src) {
+                    if self.can_fire(transition, event)? {
+                        return Ok(Some(Fired::Outgoing(dst)));
+                    }
+                }
+                return Ok(None);
+            },
+        };
+
+        let mut passing: Vec<(usize, f64)> = Vec::new();
+        for (dst, transition) in self.transitions.outgoing(src) {
+            if self.can_fire(transition, event)? {
+                passing.push((dst, transition.weight.max(0.0)));
+            }
+        }
+        let total: f64 = passing.iter().map(|&(_, weight)| weight).sum();
+        if total <= 0.0 {
+            return Ok(passing.first().map(|&(dst, _)| Fired::Outgoing(dst)));
+        }
+
+        // 53 random bits give a uniform float in [0, 1)
+        let mut point = (rng.borrow_mut().next_u64() >> 11) as f64 / (1u64 << 53) as f64 * total;
+        for &(dst, weight) in passing.iter().filter(|&&(_, weight)| weight > 0.0) {
+            if point < weight {
+                return Ok(Some(Fired::Outgoing(dst)));
+            }
+            point -= weight;
+        }
+        Ok(passing.iter().rev().find(|&&(_, weight)| weight > 0.0).map(|&(dst, _)| Fired::Outgoing(dst)))
+    }
+}
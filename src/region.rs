@@ -0,0 +1,137 @@
+use alloc::vec::Vec;
+use core::fmt::Debug;
+
+use crate::{FsmError, FsmResult, StateId, StateMachine, StepOutcome};
+
+// The runtime position of one orthogonal region, swapped into the machine while the region steps
+#[derive(Clone)]
+pub(crate) struct Region {
+    states: Vec<usize>,
+    active_state: Option<usize>,
+    active_state_initialized: bool,
+    history: Option<usize>,
+    retries: u32,
+    stuck_runs: u32,
+    #[cfg(feature = "std")]
+    entered_at: Option<std::time::Instant>,
+    #[cfg(feature = "metrics")]
+    metrics_entered_at: Option<std::time::Instant>,
+}
+
+impl Region {
+    pub(crate) fn remove_state(&mut self, index: usize) {
+        self.states.retain(|&state| state != index);
+        if self.active_state == Some(index) {
+            self.active_state = None;
+            self.active_state_initialized = false;
+        }
+        if self.history == Some(index) {
+            self.history = None;
+        }
+    }
+}
+
+impl<T: Clone, E> StateMachine<T, E> {
+    pub fn add_region<S: Into<StateId> + Copy>(&mut self, states: &[S]) -> Result<usize, FsmError> {
+        let states: Vec<usize> = states.iter().map(|&state| state.into().0).collect();
+        let states = states.as_slice();
+        for &index in states {
+            self.state(index)?;
+            if self.regions.iter().any(|region| region.states.contains(&index)) {
+                return Err(FsmError::RegionOverlap);
+            }
+        }
+        // A region only steps its own states, so no edge may cross its border in either direction
+        if !self.global_transitions.is_empty() {
+            return Err(FsmError::TransitionLeavesRegion);
+        }
+        let crosses = self.transitions_iter().map(|(src, dst, _)| (src, dst))
+            .chain(self.default_transitions.iter().flatten().map(|transition| (transition.src, transition.dst)))
+            .any(|(src, dst)| states.contains(&src) != states.contains(&dst));
+        if crosses {
+            return Err(FsmError::TransitionLeavesRegion);
+        }
+
+        self.regions.push(Region {
+            states: states.to_vec(),
+            active_state: None,
+            active_state_initialized: false,
+            history: None,
+            retries: 0,
+            stuck_runs: 0,
+            #[cfg(feature = "std")]
+            entered_at: None,
+            #[cfg(feature = "metrics")]
+            metrics_entered_at: None,
+        });
+        Ok(self.regions.len() - 1)
+    }
+
+    pub fn set_region_active(&mut self, region: usize, state: impl Into<StateId>) -> FsmResult {
+        let state = state.into().0;
+        let states = &self.regions.get(region).ok_or(FsmError::RegionIndexOutOfBounds)?.states;
+        if !states.contains(&state) {
+            return Err(FsmError::StateNotInRegion);
+        }
+
+        self.swap_region(region);
+        let result = self.set_active_state(state);
+        self.swap_region(region);
+        result
+    }
+
+    pub fn region_state(&self, region: usize) -> Option<usize> {
+        self.regions.get(region).and_then(|region| region.active_state)
+    }
+
+    fn region_of(&self, state: usize) -> Option<usize> {
+        self.regions.iter().position(|region| region.states.contains(&state))
+    }
+
+    pub(crate) fn leaves_region(&self, src: usize, dst: usize) -> bool {
+        self.region_of(src) != self.region_of(dst)
+    }
+
+    // Routing out of the active state's region would leave that region without a state
+    pub(crate) fn routes_out_of_region(&self, dst: usize) -> bool {
+        self.active_state.is_some_and(|src| self.leaves_region(src, dst))
+    }
+
+    fn swap_region(&mut self, index: usize) {
+        let region = &mut self.regions[index];
+        core::mem::swap(&mut self.active_state, &mut region.active_state);
+        core::mem::swap(&mut self.active_state_initialized, &mut region.active_state_initialized);
+        core::mem::swap(&mut self.history, &mut region.history);
+        core::mem::swap(&mut self.retries, &mut region.retries);
+        core::mem::swap(&mut self.stuck_runs, &mut region.stuck_runs);
+        #[cfg(feature = "std")]
+        core::mem::swap(&mut self.entered_at, &mut region.entered_at);
+        #[cfg(feature = "metrics")]
+        self.metrics.swap_entered_at(&mut region.metrics_entered_at);
+    }
+}
+
+impl<T: Clone, E: Clone + Debug> StateMachine<T, E> {
+    // Steps every region once in the order they were added, all regions share the data.
+    // The whole pass uses one unit of fuel
+    pub fn run_regions(&mut self) -> Vec<StepOutcome<E>> {
+        let fuel = match self.fuel.take() {
+            Some(0) => {
+                self.fuel = Some(0);
+                return self.regions.iter().map(|_| StepOutcome::Errored(FsmError::OutOfFuel.into())).collect();
+            },
+            fuel => fuel.map(|fuel| fuel - 1),
+        };
+
+        let outcomes = (0..self.regions.len())
+            .map(|index| {
+                self.swap_region(index);
+                let outcome = self.run();
+                self.swap_region(index);
+                outcome
+            })
+            .collect();
+        self.fuel = fuel;
+        outcomes
+    }
+}
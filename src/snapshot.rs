@@ -0,0 +1,33 @@
+use crate::StateMachine;
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FsmSnapshot<T> {
+    pub data: T,
+    pub active_state: Option<usize>,
+    pub active_state_initialized: bool,
+    pub history: Option<usize>,
+}
+
+impl<T: Clone, E> StateMachine<T, E> {
+    pub fn snapshot(&self) -> FsmSnapshot<T> {
+        FsmSnapshot {
+            data: self.data.clone(),
+            active_state: self.active_state,
+            active_state_initialized: self.active_state_initialized,
+            history: self.history,
+        }
+    }
+
+    // The states and transitions are expected to be unchanged since the snapshot was taken
+    pub fn restore(&mut self, snapshot: FsmSnapshot<T>) {
+        self.data = snapshot.data;
+        self.active_state = snapshot.active_state;
+        self.active_state_initialized = snapshot.active_state_initialized;
+        self.history = snapshot.history;
+        #[cfg(feature = "std")]
+        {
+            self.entered_at = self.active_state.map(|_| self.clock.now());
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use alloc::vec::Vec;
+
+use crate::Transition;
+
+pub(crate) enum TransitionTable<T: 'static + Clone, E: 'static> {
+    // Full matrix indexed by [src][dst], plus the destinations of each source in evaluation order
+    Dense {
+        matrix: Vec<Vec<Option<Transition<T, E>>>>,
+        order: Vec<Vec<usize>>,
+    },
+    // Per-source edge lists kept in evaluation order, the destination is read from each transition
+    Sparse(Vec<Vec<Transition<T, E>>>),
+}
+
+impl<T: Clone, E> Clone for TransitionTable<T, E> {
+    fn clone(&self) -> TransitionTable<T, E> {
+        match self {
+            TransitionTable::Dense { matrix, order } => TransitionTable::Dense {
+                matrix: matrix.clone(),
+                order: order.clone(),
+            },
+            TransitionTable::Sparse(lists) => TransitionTable::Sparse(lists.clone()),
+        }
+    }
+}
+
+impl<T: Clone, E> TransitionTable<T, E> {
+    pub(crate) fn dense(max_states: usize) -> TransitionTable<T, E> {
+        TransitionTable::Dense {
+            matrix: (0..max_states).map(|_| (0..max_states).map(|_| None).collect()).collect(),
+            order: (0..max_states).map(|_| Vec::new()).collect(),
+        }
+    }
+
+    pub(crate) fn sparse(max_states: usize) -> TransitionTable<T, E> {
+        TransitionTable::Sparse((0..max_states).map(|_| Vec::new()).collect())
+    }
+
+    pub(crate) fn get(&self, src: usize, dst: usize) -> Option<&Transition<T, E>> {
+        match self {
+            TransitionTable::Dense { matrix, .. } => matrix[src][dst].as_ref(),
+            TransitionTable::Sparse(lists) => lists[src].iter().find(|t| t.dst == dst),
+        }
+    }
+
+    pub(crate) fn get_mut(&mut self, src: usize, dst: usize) -> Option<&mut Transition<T, E>> {
+        match self {
+            TransitionTable::Dense { matrix, .. } => matrix[src][dst].as_mut(),
+            TransitionTable::Sparse(lists) => lists[src].iter_mut().find(|t| t.dst == dst),
+        }
+    }
+
+    pub(crate) fn row(&self, src: usize) -> Option<&[Option<Transition<T, E>>]> {
+        match self {
+            TransitionTable::Dense { matrix, .. } => Some(&matrix[src][..]),
+            TransitionTable::Sparse(_) => None,
+        }
+    }
+
+    pub(crate) fn insert(&mut self, src: usize, dst: usize, t: Transition<T, E>) {
+        // Replacing an existing transition keeps its place among equal priorities
+        match self {
+            TransitionTable::Dense { matrix, order } => {
+                if matrix[src][dst].is_none() {
+                    order[src].push(dst);
+                }
+                matrix[src][dst] = Some(t);
+            },
+            TransitionTable::Sparse(lists) => {
+                match lists[src].iter_mut().find(|existing| existing.dst == dst) {
+                    Some(existing) => *existing = t,
+                    None => lists[src].push(t),
+                }
+            },
+        }
+
+        self.sort(src);
+    }
+
+    // Higher priority first, the stable sort keeps insertion order for ties
+    pub(crate) fn sort(&mut self, src: usize) {
+        match self {
+            TransitionTable::Dense { matrix, order } => {
+                let row = &matrix[src];
+                order[src].sort_by_key(|&dst| {
+                    core::cmp::Reverse(row[dst].as_ref().map_or(i32::MIN, |transition| transition.priority))
+                });
+            },
+            TransitionTable::Sparse(lists) => {
+                lists[src].sort_by_key(|transition| core::cmp::Reverse(transition.priority));
+            },
+        }
+    }
+
+    pub(crate) fn remove(&mut self, src: usize, dst: usize) -> Option<Transition<T, E>> {
+        match self {
+            TransitionTable::Dense { matrix, order } => {
+                order[src].retain(|&index| index != dst);
+                matrix[src][dst].take()
+            },
+            TransitionTable::Sparse(lists) => {
+                let position = lists[src].iter().position(|t| t.dst == dst)?;
+                Some(lists[src].remove(position))
+            },
+        }
+    }
+
+    pub(crate) fn remove_state(&mut self, index: usize) {
+        match self {
+            TransitionTable::Dense { matrix, order } => {
+                for slot in matrix[index].iter_mut() {
+                    *slot = None;
+                }
+                order[index].clear();
+                for (row, row_order) in matrix.iter_mut().zip(order.iter_mut()) {
+                    row[index] = None;
+                    row_order.retain(|&dst| dst != index);
+                }
+            },
+            TransitionTable::Sparse(lists) => {
+                lists[index].clear();
+                for list in lists.iter_mut() {
+                    list.retain(|t| t.dst != index);
+                }
+            },
+        }
+    }
+
+    // Outgoing transitions of a source state in evaluation order
+    pub(crate) fn outgoing(&self, src: usize) -> impl Iterator<Item = (usize, &Transition<T, E>)> {
+        let (dense, sparse) = match self {
+            TransitionTable::Dense { matrix, order } => (Some((&matrix[src], &order[src])), None),
+            TransitionTable::Sparse(lists) => (None, Some(&lists[src])),
+        };
+
+        dense.into_iter()
+            .flat_map(|(row, order)| order.iter().filter_map(move |&dst| row[dst].as_ref().map(|t| (dst, t))))
+            .chain(sparse.into_iter().flat_map(|list| list.iter().map(|t| (t.dst, t))))
+    }
+
+    // All transitions by source, dense tables list destinations by index and sparse ones in evaluation order
+    pub(crate) fn iter(&self, num_states: usize) -> impl Iterator<Item = (usize, usize, &Transition<T, E>)> {
+        let (dense, sparse) = match self {
+            TransitionTable::Dense { matrix, .. } => (Some(&matrix[..num_states]), None),
+            TransitionTable::Sparse(lists) => (None, Some(&lists[..num_states])),
+        };
+
+        dense.into_iter()
+            .flat_map(move |matrix| matrix.iter().enumerate().flat_map(move |(src, row)| {
+                row[..num_states].iter()
+                    .enumerate()
+                    .filter_map(move |(dst, t)| t.as_ref().map(|transition| (src, dst, transition)))
+            }))
+            .chain(sparse.into_iter().flat_map(|lists| lists.iter().enumerate().flat_map(|(src, list)| {
+                list.iter().map(move |transition| (src, transition.dst, transition))
+            })))
+    }
+
+    pub(crate) fn has_outgoing(&self, src: usize) -> bool {
+        self.outgoing(src).next().is_some()
+    }
+}
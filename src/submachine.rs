@@ -0,0 +1,40 @@
+use alloc::rc::Rc;
+use core::cell::RefCell;
+use core::fmt::Debug;
+
+use crate::{FsmError, FsmErrorKind, State, StateMachine};
+
+impl<T: Clone, E: Clone + Debug + From<FsmError>> State<T, E> {
+    // Entering the state resets the child to its initial state, each exec runs it until it stays or halts.
+    // A child without an initial state starts from its active one, with neither it's rejected here.
+    // The child works on the parent's data, its own data is only a placeholder swapped out meanwhile.
+    // Clones of the state share the same child.
+    pub fn sub_machine<'b>(name: impl Into<alloc::borrow::Cow<'b, str>>,
+                           mut child: StateMachine<T, E>,
+                           max_steps: usize
+    ) -> Result<State<T, E>, FsmError> {
+        if child.initial_state.is_none() {
+            let active = child.active_state.ok_or(FsmError::NoActiveState)?;
+            child.set_initial(active)?;
+        }
+        let child = Rc::new(RefCell::new(child));
+        let exec_child = child.clone();
+
+        Ok(State::new(name,
+            move |_state: &State<T, E>, _data: &mut T| {
+                child.borrow_mut().reset();
+                Ok(())
+            },
+            move |_state: &State<T, E>, data: &mut T| {
+                let mut child = exec_child.borrow_mut();
+                core::mem::swap(&mut child.data, data);
+                let result = child.run_until_stable(max_steps);
+                core::mem::swap(&mut child.data, data);
+
+                result.map(|_| ()).map_err(|error| match error {
+                    FsmErrorKind::User(e) => e,
+                    FsmErrorKind::Structural(e) => E::from(e),
+                })
+            }))
+    }
+}
@@ -0,0 +1,67 @@
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{FsmError, State, StateCallback, StateMachine, TransCheckCallback, TransDoneCallback, Transition};
+
+pub type StateCallbacks<T, E = FsmError> = (Rc<StateCallback<T, E>>, Rc<StateCallback<T, E>>);
+pub type TransitionCallbacks<T, E = FsmError> = (Rc<TransCheckCallback<T, E>>, Rc<TransDoneCallback<T, E>>);
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FsmTopology {
+    pub states: Vec<Option<String>>,
+    pub transitions: Vec<(usize, usize, String)>,
+    pub active_state: Option<usize>,
+    pub num_states: usize,
+}
+
+impl<T: Clone, E> StateMachine<T, E> {
+    pub fn topology(&self) -> FsmTopology {
+        let states = self.states[..self.num_states].iter()
+            .map(|s| s.as_ref().map(|state| state.name.clone()))
+            .collect();
+
+        let transitions = self.transitions_iter()
+            .map(|(src, dst, transition)| (src, dst, transition.name.clone()))
+            .collect();
+
+        FsmTopology {
+            states,
+            transitions,
+            active_state: self.active_state,
+            num_states: self.num_states,
+        }
+    }
+
+    // Rebuilds the states and transitions of a topology, callbacks are looked up by state and transition name
+    pub fn from_topology(data: T,
+                         topology: &FsmTopology,
+                         states: impl Fn(&str) -> Option<StateCallbacks<T, E>>,
+                         transitions: impl Fn(&str) -> Option<TransitionCallbacks<T, E>>
+    ) -> Result<StateMachine<T, E>, FsmError> {
+        let mut sm = StateMachine::new(data, topology.num_states.max(topology.states.len()));
+
+        for name in topology.states.iter() {
+            match name {
+                Some(name) => {
+                    let (init, exec) = states(name).ok_or(FsmError::UnresolvedName)?;
+                    sm.add_state(State { init, exec, ..State::new(name.as_str(), State::noop_init, State::noop_exec) })?;
+                },
+                // Keep removed slots empty so the indices match the topology
+                None => sm.num_states += 1,
+            }
+        }
+
+        for (src, dst, name) in topology.transitions.iter() {
+            let (check, done) = transitions(name).ok_or(FsmError::UnresolvedName)?;
+            let transition = Transition { check, done, ..Transition::new(name.as_str(), *src, *dst, Transition::never, crate::noop_done) };
+            sm.place_transition(transition)?;
+        }
+
+        if let Some(index) = topology.active_state {
+            sm.set_active_state(index)?;
+        }
+        Ok(sm)
+    }
+}
@@ -0,0 +1,62 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::time::Duration;
+
+use crate::{FsmErrorKind, StateMachine};
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TraceEntry {
+    // Time since recording started, always zero without std
+    pub at: Duration,
+    pub src: usize,
+    pub dst: usize,
+    pub transition: String,
+}
+
+#[derive(Clone)]
+pub(crate) struct Recorder {
+    entries: Vec<TraceEntry>,
+    #[cfg(feature = "std")]
+    started: std::time::Instant,
+}
+
+impl<T: Clone, E> StateMachine<T, E> {
+    pub fn record_trace(&mut self, enabled: bool) {
+        self.trace = enabled.then(|| Recorder {
+            entries: Vec::new(),
+            #[cfg(feature = "std")]
+            started: self.clock.now(),
+        });
+    }
+
+    // Hands over the transitions recorded so far, recording continues while enabled
+    pub fn take_trace(&mut self) -> Vec<TraceEntry> {
+        self.trace.as_mut().map(|recorder| core::mem::take(&mut recorder.entries)).unwrap_or_default()
+    }
+
+    pub(crate) fn record(&mut self, src: usize, dst: usize, transition: &str) {
+        #[cfg(feature = "std")]
+        let clock = &self.clock;
+        if let Some(ref mut recorder) = self.trace {
+            #[cfg(feature = "std")]
+            let at = clock.now() - recorder.started;
+            #[cfg(not(feature = "std"))]
+            let at = Duration::ZERO;
+            recorder.entries.push(TraceEntry { at, src, dst, transition: String::from(transition) });
+        }
+    }
+}
+
+impl<T: Clone, E: Clone + core::fmt::Debug> StateMachine<T, E> {
+    // Forces the recorded transitions in order, starting from the source of the first one
+    pub fn replay(&mut self, trace: &[TraceEntry]) -> Result<(), FsmErrorKind<E>> {
+        for entry in trace {
+            if self.active_state != Some(entry.src) {
+                self.set_active_state(entry.src)?;
+            }
+            self.force_transition(entry.dst)?;
+        }
+        Ok(())
+    }
+}
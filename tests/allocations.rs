@@ -0,0 +1,65 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering;
+    use call_fsm::{*};
+    use super::ALLOCATIONS;
+
+    fn count_exec(_s: &State<u32>, data: &mut u32) -> Result<(), FsmError> {
+        *data += 1;
+        Ok(())
+    }
+
+    fn every_tenth(_t: &Transition<u32>, data: &u32) -> bool {
+        data.is_multiple_of(10)
+    }
+
+    #[test]
+    fn run_does_not_clone_states_or_transitions() {
+        let data = 1;
+
+        declare_data_type!(u32);
+        declare_state_machine!(sm, data, 2);
+
+        new_state!(sm, ping, &State::noop_init, &count_exec);
+        new_state!(sm, pong, &State::noop_init, &count_exec);
+
+        new_transition!(sm, ping, pong, &every_tenth, &noop_done);
+        new_transition!(sm, pong, ping, &every_tenth, &noop_done);
+
+        sm.set_active_state(ping).unwrap();
+
+        let mut transitions = 0;
+        let before = ALLOCATIONS.load(Ordering::SeqCst);
+        for _ in 0..100 {
+            if let StepOutcome::Transitioned { .. } = sm.run() {
+                transitions += 1;
+            }
+        }
+        let allocations = ALLOCATIONS.load(Ordering::SeqCst) - before;
+
+        // Only the transition name reported in the outcome is allocated
+        assert_eq!(transitions, 10);
+        assert_eq!(allocations, transitions);
+    }
+}
@@ -0,0 +1,91 @@
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+
+    fn init_sm() -> StateMachine<u32> {
+        let data = 0;
+
+        declare_data_type!(u32);
+        declare_state_machine!(sm, data, 5);
+
+        new_state!(sm, state1, &State::noop_init, &State::noop_exec);
+        new_state!(sm, state2, &State::noop_init, &State::noop_exec);
+        new_state!(sm, state3, &State::noop_init, &State::noop_exec);
+        new_state!(sm, orphan, &State::noop_init, &State::noop_exec);
+
+        new_transition!(sm, state1, state2, &always, &noop_done);
+        new_transition!(sm, state2, state3, &always, &noop_done);
+        new_transition!(sm, orphan, state1, &always, &noop_done);
+
+        sm
+    }
+
+    #[test]
+    fn unreachable_states_from_initial() {
+        let sm = init_sm();
+
+        assert_eq!(sm.unreachable_states(0), vec![3]);
+        assert_eq!(sm.unreachable_states(2), vec![0, 1, 3]);
+        assert!(sm.unreachable_states(3).is_empty());
+    }
+
+    #[test]
+    fn dead_end_states_have_no_outgoing_transitions() {
+        let mut sm = init_sm();
+        assert_eq!(sm.dead_end_states(), vec![2]);
+
+        sm.remove_state(2).unwrap();
+        assert_eq!(sm.dead_end_states(), vec![1]);
+    }
+
+    #[test]
+    fn shortest_path_between_states() {
+        let sm = init_sm();
+
+        assert_eq!(sm.shortest_path(3, 2), Some(vec![3, 0, 1, 2]));
+        assert_eq!(sm.shortest_path(1, 1), Some(vec![1]));
+        assert_eq!(sm.shortest_path(2, 0), None);
+        assert_eq!(sm.shortest_path(0, 9), None);
+    }
+
+    #[test]
+    fn degrees() {
+        let sm = init_sm();
+
+        assert_eq!(sm.out_degree(0), Ok(1));
+        assert_eq!(sm.in_degree(0), Ok(1));
+        assert_eq!(sm.out_degree(2), Ok(0));
+        assert_eq!(sm.in_degree(3), Ok(0));
+        assert_eq!(sm.in_degree(4), Err(FsmError::StateIndexOutOfBounds));
+    }
+
+    #[test]
+    fn cycle_detection() {
+        let mut sm = init_sm();
+        assert!(!sm.has_cycle());
+        assert_eq!(sm.find_cycle(), None);
+
+        let back: Transition<u32> = Transition::new("back", 2, 0, always, noop_done);
+        sm.add_transition(back, 2, 0).unwrap();
+        assert!(sm.has_cycle());
+        assert_eq!(sm.find_cycle(), Some(vec![0, 1, 2]));
+
+        sm.remove_transition(2, 0).unwrap();
+        let again: Transition<u32> = Transition::new("again", 1, 1, always, noop_done);
+        sm.add_self_transition(again, 1).unwrap();
+        assert_eq!(sm.find_cycle(), Some(vec![1]));
+    }
+
+    #[test]
+    fn topological_order_follows_transitions() {
+        let mut sm = init_sm();
+        assert_eq!(sm.topological_order(), Ok(vec![3, 0, 1, 2]));
+
+        let back: Transition<u32> = Transition::new("back", 2, 0, always, noop_done);
+        sm.add_transition(back, 2, 0).unwrap();
+        assert_eq!(sm.topological_order(), Err(FsmError::CyclicGraph));
+
+        sm.remove_state(0).unwrap();
+        assert_eq!(sm.topological_order(), Ok(vec![1, 3, 2]));
+    }
+}
@@ -0,0 +1,64 @@
+#![cfg(feature = "async")]
+
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+    use std::future::Future;
+    use std::pin::pin;
+    use std::task::{Context, Poll, Waker};
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        let mut cx = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+    }
+
+    fn noop_state(_data: &mut u32) -> FsmFuture<'_> {
+        Box::pin(async { Ok(()) })
+    }
+
+    fn count_exec(data: &mut u32) -> FsmFuture<'_> {
+        Box::pin(async move {
+            *data += 1;
+            Ok(())
+        })
+    }
+
+    fn failing_done(_data: &mut u32) -> FsmFuture<'_> {
+        Box::pin(async { Err(FsmError::StateIsEmpty) })
+    }
+
+    fn init_sm() -> AsyncStateMachine<u32> {
+        let mut sm = AsyncStateMachine::new(0, 3);
+        sm.add_state(AsyncState::new("idle", noop_state, count_exec)).unwrap();
+        sm.add_state(AsyncState::new("busy", noop_state, count_exec)).unwrap();
+        sm.add_state(AsyncState::new("broken", noop_state, count_exec)).unwrap();
+        sm
+    }
+
+    #[test]
+    fn async_run_awaits_callbacks() {
+        let mut sm = init_sm();
+        sm.add_transition(AsyncTransition::new("go", 0, 1, |data: &u32| *data >= 2, noop_state), 0, 1).unwrap();
+        sm.set_active_state(0).unwrap();
+
+        assert_eq!(block_on(sm.run()), StepOutcome::Stayed(0));
+        assert_eq!(block_on(sm.run()), StepOutcome::Transitioned { from: 0, to: 1, via: String::from("go") });
+        assert_eq!(block_on(sm.run()), StepOutcome::Stayed(1));
+        assert_eq!(*sm.data(), 3);
+    }
+
+    #[test]
+    fn async_done_error() {
+        let mut sm = init_sm();
+        sm.add_transition(AsyncTransition::new("fail", 0, 2, |_: &u32| true, failing_done), 0, 2).unwrap();
+        sm.set_active_state(0).unwrap();
+
+        assert_eq!(block_on(sm.run()), StepOutcome::Errored(FsmErrorKind::User(FsmError::StateIsEmpty)));
+        assert_eq!(sm.current_state_index(), Some(0));
+    }
+}
@@ -0,0 +1,61 @@
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+
+    fn init_sm() -> StateMachine<u32> {
+        let data = 0;
+
+        declare_data_type!(u32);
+        declare_state_machine!(sm, data, 3);
+
+        new_state!(sm, idle, &State::noop_init, &State::noop_exec);
+        new_state!(sm, busy, &State::noop_init, &State::noop_exec);
+        new_state!(sm, failed, &State::noop_init, &State::noop_exec);
+
+        new_transition!(sm, idle, busy, &always, &noop_done);
+        new_transition!(sm, idle, failed, &never, &noop_done);
+
+        sm
+    }
+
+    #[test]
+    fn available_and_enabled() {
+        let mut sm = init_sm();
+        assert!(sm.available_transitions().is_empty());
+
+        sm.set_active_state(0).unwrap();
+        assert_eq!(sm.available_transitions(), vec![(1, "idle__busy"), (2, "idle__failed")]);
+        assert_eq!(sm.enabled_transitions(), vec![(1, "idle__busy")]);
+
+        sm.set_active_state(1).unwrap();
+        assert!(sm.available_transitions().is_empty());
+    }
+
+    #[test]
+    fn peek_does_not_step() {
+        let mut sm = init_sm();
+        assert_eq!(sm.peek_transition(), None);
+
+        sm.set_active_state(0).unwrap();
+        assert_eq!(sm.peek_transition(), Some((1, "idle__busy")));
+        assert_eq!(sm.current_state_index(), Some(0));
+
+        sm.set_active_state(1).unwrap();
+        assert_eq!(sm.peek_transition(), None);
+    }
+
+    #[test]
+    fn disabled_transition_skipped() {
+        let mut sm = init_sm();
+        sm.set_active_state(0).unwrap();
+
+        sm.set_transition_enabled(0, 1, false).unwrap();
+        assert_eq!(sm.available_transitions(), vec![(1, "idle__busy"), (2, "idle__failed")]);
+        assert!(sm.enabled_transitions().is_empty());
+        assert_eq!(sm.run(), StepOutcome::Stayed(0));
+
+        sm.set_transition_enabled(0, 1, true).unwrap();
+        assert_eq!(sm.run(), StepOutcome::Transitioned { from: 0, to: 1, via: String::from("idle__busy") });
+        assert_eq!(sm.set_transition_enabled(1, 0, false), Err(FsmError::TransitionIsEmpty));
+    }
+}
@@ -0,0 +1,61 @@
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+
+    fn count_exec(_s: &State<u32>, data: &mut u32) -> Result<(), FsmError> {
+        *data += 1;
+        Ok(())
+    }
+
+    fn no_route(_e: FsmErrorKind, _data: &mut u32) -> Option<Destination> {
+        None
+    }
+
+    fn build_generic<T: Clone>(data: T) -> Result<StateMachine<T>, FsmError> {
+        StateMachineBuilder::new(data, 2)
+            .state("first", |_s: &State<T>, _data: &mut T| Ok(()), |_s: &State<T>, _data: &mut T| Ok(()))
+            .state("second", |_s: &State<T>, _data: &mut T| Ok(()), |_s: &State<T>, _data: &mut T| Ok(()))
+            .transition(0, 1, "first__second", |_t: &Transition<T>, _data: &T| true, |_t: &Transition<T>, _data: &mut T| Ok(()))
+            .initial(0)
+            .build()
+    }
+
+    #[test]
+    fn builder_creates_runnable_machine() {
+        let mut sm = StateMachineBuilder::new(0, 2)
+            .state("idle", State::noop_init, count_exec)
+            .state("busy", State::noop_init, count_exec)
+            .transition(StateId(0), StateId(1), "idle__busy", always, noop_done)
+            .initial(0)
+            .on_error(no_route, no_route)
+            .build()
+            .unwrap();
+
+        assert_eq!(sm.run(), StepOutcome::Transitioned { from: 0, to: 1, via: String::from("idle__busy") });
+        assert_eq!(*sm.data(), 1);
+
+        let mut sm = build_generic(String::from("data")).unwrap();
+        assert!(matches!(sm.run(), StepOutcome::Transitioned { from: 0, to: 1, .. }));
+    }
+
+    #[test]
+    fn builder_reports_authoring_errors() {
+        let result = StateMachineBuilder::<u32>::new(0, 1)
+            .state("idle", State::noop_init, State::noop_exec)
+            .state("busy", State::noop_init, State::noop_exec)
+            .build();
+        assert_eq!(result.err(), Some(FsmError::MaxNumberOfStatesExceeded));
+
+        let result = StateMachineBuilder::<u32>::new(0, 2)
+            .state("idle", State::noop_init, State::noop_exec)
+            .transition(0, 1, "idle__busy", always, noop_done)
+            .build();
+        assert_eq!(result.err(), Some(FsmError::TransitionIndexOutOfBounds));
+
+        let result = StateMachineBuilder::<u32>::new(0, 2)
+            .state("idle", State::noop_init, State::noop_exec)
+            .initial(1)
+            .build();
+        assert_eq!(result.err(), Some(FsmError::StateIndexOutOfBounds));
+    }
+}
@@ -0,0 +1,72 @@
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+
+    fn count_exec(_s: &State<u32>, data: &mut u32) -> Result<(), FsmError> {
+        *data += 1;
+        Ok(())
+    }
+
+    #[test]
+    fn add_states_shares_callbacks() {
+        let mut sm: StateMachine<u32> = StateMachine::new(0, 4);
+
+        let indices = sm.add_states(&["red", "green", "blue"], State::noop_init, count_exec).unwrap();
+        assert_eq!(indices, vec![StateId(0), StateId(1), StateId(2)]);
+        assert_eq!(sm.state_by_name("blue"), Some(2));
+
+        for index in indices {
+            sm.set_active_state(index).unwrap();
+            sm.run();
+        }
+        assert_eq!(*sm.data(), 3);
+    }
+
+    #[test]
+    fn add_states_is_all_or_nothing() {
+        let mut sm: StateMachine<u32> = StateMachine::new(0, 4);
+        sm.add_states(&["red"], State::noop_init, State::noop_exec).unwrap();
+
+        assert_eq!(sm.add_states(&["a", "b", "c", "d"], State::noop_init, State::noop_exec), Err(FsmError::MaxNumberOfStatesExceeded));
+        assert_eq!(sm.add_states(&["a", "red"], State::noop_init, State::noop_exec), Err(FsmError::DuplicateStateName));
+        assert_eq!(sm.add_states(&["a", "a"], State::noop_init, State::noop_exec), Err(FsmError::DuplicateStateName));
+        assert_eq!(sm.states_iter().count(), 1);
+    }
+
+    fn ticks_reached(_t: &Transition<u32>, data: &u32) -> bool {
+        *data >= 1
+    }
+
+    fn reset_done(_t: &Transition<u32>, data: &mut u32) -> Result<(), FsmError> {
+        *data = 0;
+        Ok(())
+    }
+
+    #[test]
+    fn add_transitions_wires_a_ring() {
+        let mut sm: StateMachine<u32> = StateMachine::new(0, 3);
+        let [red, green, blue] = sm.add_states(&["red", "green", "blue"], State::noop_init, count_exec).unwrap()[..] else {
+            panic!("Failed to add states");
+        };
+
+        sm.add_transitions(&[(red, green, "go"), (green, blue, "slow"), (blue, red, "stop")], ticks_reached, reset_done).unwrap();
+        assert_eq!(sm.transition_by_name("slow"), Some((green.0, blue.0)));
+
+        sm.set_active_state(red).unwrap();
+        assert_eq!(sm.run_steps(3), vec![
+            StepOutcome::Transitioned { from: red.0, to: green.0, via: String::from("go") },
+            StepOutcome::Transitioned { from: green.0, to: blue.0, via: String::from("slow") },
+            StepOutcome::Transitioned { from: blue.0, to: red.0, via: String::from("stop") },
+        ]);
+    }
+
+    #[test]
+    fn add_transitions_reports_bad_spec() {
+        let mut sm: StateMachine<u32> = StateMachine::new(0, 3);
+        sm.add_states(&["red", "green"], State::noop_init, State::noop_exec).unwrap();
+
+        assert_eq!(sm.add_transitions(&[(0, 1, "go"), (1, 2, "slow")], ticks_reached, reset_done),
+                   Err((1, FsmError::TransitionIndexOutOfBounds)));
+        assert_eq!(sm.transitions_iter().count(), 0);
+    }
+}
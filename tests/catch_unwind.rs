@@ -0,0 +1,84 @@
+#![cfg(feature = "catch-unwind")]
+
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+
+    fn panicking_exec(_s: &State<u32>, _data: &mut u32) -> Result<(), FsmError> {
+        panic!("exec blew up");
+    }
+
+    fn panicking_check(_t: &Transition<u32>, _data: &u32) -> bool {
+        panic!("guard blew up");
+    }
+
+    fn ignore_error(_e: FsmErrorKind, _data: &mut u32) -> Option<Destination> {
+        None
+    }
+
+    fn route_to_safe(_e: FsmErrorKind, _data: &mut u32) -> Option<Destination> {
+        Some(Destination::Name(String::from("safe")))
+    }
+
+    #[test]
+    fn panic_becomes_error() {
+        let data = 0;
+
+        declare_data_type!(u32);
+        declare_state_machine!(sm, data, 2);
+
+        new_state!(sm, working, &State::noop_init, &panicking_exec);
+        new_state!(sm, safe, &State::noop_init, &State::noop_exec);
+
+        sm.set_error_callbacks(ignore_error, route_to_safe);
+        sm.set_active_state(working).unwrap();
+
+        assert_eq!(sm.run(), StepOutcome::Errored(FsmError::CallbackPanicked.into()));
+        assert_eq!(sm.current_state_id(), Some(safe));
+        assert_eq!(sm.run(), StepOutcome::Stayed(safe.0));
+    }
+
+    #[test]
+    fn guard_panic_becomes_error() {
+        let data = 0;
+
+        declare_data_type!(u32);
+        declare_state_machine!(sm, data, 3);
+
+        new_state!(sm, working, &State::noop_init, &State::noop_exec);
+        new_state!(sm, done, &State::noop_init, &State::noop_exec);
+        new_state!(sm, safe, &State::noop_init, &State::noop_exec);
+        new_transition!(sm, working, done, &panicking_check, &noop_done);
+
+        sm.set_error_callbacks_with_phase(|_e, _phase, _data| None, |_e, phase, data| {
+            *data = (phase == Phase::Guard) as u32;
+            Some(Destination::Name(String::from("safe")))
+        });
+        sm.set_active_state(working).unwrap();
+
+        assert_eq!(sm.run(), StepOutcome::Errored(FsmError::CallbackPanicked.into()));
+        assert_eq!(sm.current_state_id(), Some(safe));
+        assert_eq!(*sm.data(), 1);
+        assert_eq!(sm.peek_transition(), None);
+    }
+
+    #[test]
+    fn observer_panic_becomes_error() {
+        let data = 0;
+
+        declare_data_type!(u32);
+        declare_state_machine!(sm, data, 2);
+
+        new_state!(sm, working, &State::noop_init, &State::noop_exec);
+        new_state!(sm, done, &State::noop_init, &State::noop_exec);
+        new_transition!(sm, working, done, &always, &noop_done);
+
+        sm.set_transition_observer(|_src, _dst, _via, _data| panic!("observer blew up"));
+        sm.set_error_callbacks(|_e, _data| None, |_e, _data| panic!("error callback blew up"));
+        sm.set_active_state(working).unwrap();
+
+        assert_eq!(sm.run(), StepOutcome::Errored(FsmError::CallbackPanicked.into()));
+        assert_eq!(sm.current_state_id(), Some(working));
+        assert_eq!(sm.run(), StepOutcome::Errored(FsmError::CallbackPanicked.into()));
+    }
+}
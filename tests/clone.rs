@@ -0,0 +1,34 @@
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+
+    fn count_exec(_s: &State<u32>, data: &mut u32) -> Result<(), FsmError> {
+        *data += 1;
+        Ok(())
+    }
+
+    #[test]
+    fn clone_forks_execution() {
+        let data = 0;
+
+        declare_data_type!(u32);
+        declare_state_machine!(sm, data, 2);
+
+        new_state!(sm, ping, &State::noop_init, &count_exec);
+        new_state!(sm, pong, &State::noop_init, &count_exec);
+
+        new_transition!(sm, ping, pong, &always, &noop_done);
+        new_transition!(sm, pong, ping, &always, &noop_done);
+
+        sm.set_active_state(ping).unwrap();
+        sm.run();
+
+        let mut fork = sm.clone();
+        fork.run_steps(3);
+
+        assert_eq!(*sm.data(), 1);
+        assert_eq!(sm.current_state_id(), Some(pong));
+        assert_eq!(*fork.data(), 4);
+        assert_eq!(fork.current_state_id(), Some(ping));
+    }
+}
@@ -0,0 +1,137 @@
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+
+    #[derive(Clone)]
+    struct Status {
+        pub log: Vec<String>,
+    }
+
+    fn init_sm(prefix: &str, limit: usize) -> StateMachine<Status> {
+        let status = Status { log: Vec::new() };
+
+        declare_data_type!(Status);
+        declare_state_machine!(sm, status, 2);
+
+        let exec_prefix = String::from(prefix);
+        new_state!(sm, first,
+            |_s: &State<Status>, _data: &mut Status| Ok(()),
+            move |s: &State<Status>, data: &mut Status| {
+                data.log.push(format!("{}{}", exec_prefix, s.name));
+                Ok(())
+            }
+        );
+
+        new_state!(sm, second,
+            |_s: &State<Status>, _data: &mut Status| Ok(()),
+            |_s: &State<Status>, _data: &mut Status| Ok(())
+        );
+
+        new_transition!(sm, first, second,
+            move |_t: &Transition<Status>, data: &Status| data.log.len() >= limit,
+            |_t: &Transition<Status>, _data: &mut Status| Ok(())
+        );
+
+        sm
+    }
+
+    #[test]
+    fn closures_capture_local_context() {
+        let mut sm = init_sm("> ", 2);
+        sm.set_active_state(0).unwrap();
+
+        assert_eq!(sm.run(), StepOutcome::Stayed(0));
+        assert!(matches!(sm.run(), StepOutcome::Transitioned { from: 0, to: 1, .. }));
+        assert_eq!(sm.data().log, vec![String::from("> first"), String::from("> first")]);
+    }
+
+    #[test]
+    fn fn_mut_callbacks_keep_own_state() {
+        let mut sm: StateMachine<Status> = StateMachine::new(Status { log: Vec::new() }, 2);
+
+        let mut runs = 0;
+        let counting = State::new_mut("counting",
+            |_s: &State<Status>, _data: &mut Status| Ok(()),
+            move |_s: &State<Status>, data: &mut Status| {
+                runs += 1;
+                data.log.push(format!("run {}", runs));
+                Ok(())
+            });
+        sm.add_state(counting).unwrap();
+        let done = State::new("done",
+            |_s: &State<Status>, _data: &mut Status| Ok(()),
+            |_s: &State<Status>, _data: &mut Status| Ok(()));
+        sm.add_state(done).unwrap();
+
+        let mut fired = 0;
+        let finish = Transition::new_mut("finish", 0, 1,
+            |_t: &Transition<Status>, data: &Status| data.log.len() >= 2,
+            move |_t: &Transition<Status>, data: &mut Status| {
+                fired += 1;
+                data.log.push(format!("fired {}", fired));
+                Ok(())
+            });
+        sm.add_transition(finish, 0, 1).unwrap();
+
+        sm.set_active_state(0).unwrap();
+        sm.run_steps(2);
+        assert_eq!(sm.data().log, vec![String::from("run 1"), String::from("run 2"), String::from("fired 1")]);
+    }
+
+    #[test]
+    fn done_sees_both_states() {
+        let status = Status { log: Vec::new() };
+
+        declare_data_type!(Status);
+        declare_state_machine!(sm, status, 2);
+
+        new_state!(sm, parked, &State::noop_init, &State::noop_exec);
+        new_state!(sm, driving, &State::noop_init, &State::noop_exec);
+
+        let depart = Transition::with_states("depart", parked, driving, Transition::always,
+            |_t: &Transition<Status>, src: &State<Status>, dst: &State<Status>, data: &mut Status| {
+                data.log.push(format!("transitioning from {} to {}", src.name, dst.name));
+                Ok(())
+            });
+        sm.add_transition(depart, parked, driving).unwrap();
+
+        sm.set_active_state(parked).unwrap();
+        sm.run();
+        sm.force_transition(parked).unwrap();
+        sm.set_active_state(parked).unwrap();
+        sm.force_transition(driving).unwrap();
+        assert_eq!(sm.data().log, vec![
+            String::from("transitioning from parked to driving"),
+            String::from("transitioning from parked to driving"),
+        ]);
+    }
+
+    #[test]
+    fn replaced_callbacks_drop_state_aware_done() {
+        let status = Status { log: Vec::new() };
+
+        declare_data_type!(Status);
+        declare_state_machine!(sm, status, 2);
+
+        new_state!(sm, parked, &State::noop_init, &State::noop_exec);
+        new_state!(sm, driving, &State::noop_init, &State::noop_exec);
+
+        let depart = Transition::with_states("depart", parked, driving, Transition::always,
+            |_t: &Transition<Status>, _src: &State<Status>, _dst: &State<Status>, data: &mut Status| {
+                data.log.push(String::from("old"));
+                Ok(())
+            });
+        sm.add_transition(depart, parked, driving).unwrap();
+        assert!(sm.transition(parked, driving).unwrap().done_between().is_some());
+
+        sm.set_transition_callbacks(parked, driving, Transition::always, |_t, data: &mut Status| {
+            data.log.push(String::from("new"));
+            Ok(())
+        }).unwrap();
+        assert!(sm.transition(parked, driving).unwrap().done_between().is_none());
+
+        sm.set_active_state(parked).unwrap();
+        sm.run();
+        assert_eq!(sm.data().log, vec![String::from("new")]);
+    }
+}
@@ -0,0 +1,41 @@
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+
+    fn collect(_s: &State<Vec<u32>>, data: &mut Vec<u32>) -> Result<(), FsmError> {
+        data.push(data.len() as u32);
+        Ok(())
+    }
+
+    fn init_sm() -> StateMachine<Vec<u32>> {
+        let data = Vec::new();
+
+        declare_data_type!(Vec<u32>);
+        declare_state_machine!(sm, data, 1);
+
+        new_state!(sm, collecting, &State::noop_init, &collect);
+
+        sm.set_initial(collecting).unwrap();
+        sm.set_active_state(collecting).unwrap();
+        sm
+    }
+
+    #[test]
+    fn into_data_returns_result() {
+        let mut sm = init_sm();
+        sm.run_steps(3);
+
+        assert_eq!(sm.into_data(), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn replace_data_between_runs() {
+        let mut sm = init_sm();
+        sm.run_steps(2);
+
+        assert_eq!(sm.replace_data(vec![7]), vec![0, 1]);
+        sm.reset();
+        sm.run();
+        assert_eq!(*sm.data(), vec![7, 1]);
+    }
+}
@@ -0,0 +1,58 @@
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+
+    #[derive(Clone, Copy)]
+    struct Reading {
+        pub value: i32,
+        pub fallbacks: u32,
+    }
+
+    fn is_high(_t: &Transition<Reading>, data: &Reading) -> bool {
+        data.value > 10
+    }
+
+    fn is_low(_t: &Transition<Reading>, data: &Reading) -> bool {
+        data.value < 0
+    }
+
+    fn count_fallback(_t: &Transition<Reading>, data: &mut Reading) -> Result<(), FsmError> {
+        data.fallbacks += 1;
+        Ok(())
+    }
+
+    fn init_sm(value: i32) -> StateMachine<Reading> {
+        let reading = Reading { value, fallbacks: 0 };
+
+        declare_data_type!(Reading);
+        declare_state_machine!(sm, reading, 4);
+
+        new_state!(sm, sample, &State::noop_init, &State::noop_exec);
+        new_state!(sm, high, &State::noop_init, &State::noop_exec);
+        new_state!(sm, low, &State::noop_init, &State::noop_exec);
+        new_state!(sm, idle, &State::noop_init, &State::noop_exec);
+
+        new_transition!(sm, sample, high, &is_high, &noop_done);
+        new_transition!(sm, sample, low, &is_low, &noop_done);
+
+        let otherwise: Transition<Reading> = Transition::new("otherwise", sample, idle, never, count_fallback);
+        sm.add_default_transition(otherwise, sample).unwrap();
+
+        sm.set_active_state(sample).unwrap();
+        sm
+    }
+
+    #[test]
+    fn default_transition_fires_when_no_guard_passes() {
+        let mut sm = init_sm(5);
+        assert_eq!(sm.run(), StepOutcome::Transitioned { from: 0, to: 3, via: String::from("otherwise") });
+        assert_eq!(sm.data().fallbacks, 1);
+    }
+
+    #[test]
+    fn default_transition_skipped_when_guard_passes() {
+        let mut sm = init_sm(20);
+        assert_eq!(sm.run(), StepOutcome::Transitioned { from: 0, to: 1, via: String::from("sample__high") });
+        assert_eq!(sm.data().fallbacks, 0);
+    }
+}
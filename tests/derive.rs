@@ -0,0 +1,36 @@
+#![cfg(feature = "derive")]
+
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+
+    #[derive(FsmStates)]
+    #[allow(dead_code)]
+    enum Screen {
+        Home,
+        Settings,
+        AboutPage,
+    }
+
+    #[test]
+    fn derived_constants_and_names() {
+        assert_eq!(Screen::HOME, 0);
+        assert_eq!(Screen::ABOUT_PAGE, 2);
+        assert_eq!(Screen::Settings.index(), Screen::SETTINGS);
+        assert_eq!(Screen::AboutPage.index(), Screen::AboutPage as usize);
+        assert_eq!(Screen::name(Screen::ABOUT_PAGE), Some("AboutPage"));
+        assert_eq!(Screen::name(3), None);
+        assert_eq!(Screen::from_name("Settings"), Some(1));
+        assert_eq!(Screen::from_name("Missing"), None);
+    }
+
+    #[test]
+    fn derived_names_match_states() {
+        let mut sm: StateMachine<u32> = StateMachine::new(0, Screen::NAMES.len());
+        sm.add_states(Screen::NAMES, State::noop_init, State::noop_exec).unwrap();
+
+        sm.set_active_state(Screen::Settings as usize).unwrap();
+        assert_eq!(sm.current_state_name(), Screen::name(Screen::SETTINGS));
+        assert_eq!(sm.state_by_name("Home"), Some(Screen::HOME));
+    }
+}
@@ -0,0 +1,60 @@
+#![cfg(feature = "heapless")]
+
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+
+    fn noop_state(_data: &mut u32) -> Result<(), FsmError> {
+        Ok(())
+    }
+
+    fn count_exec(data: &mut u32) -> Result<(), FsmError> {
+        *data += 1;
+        Ok(())
+    }
+
+    fn always(_data: &u32) -> bool {
+        true
+    }
+
+    fn noop_done(_data: &mut u32) -> Result<(), FsmError> {
+        Ok(())
+    }
+
+    #[test]
+    fn embedded_machine_runs() {
+        let mut sm: EmbeddedStateMachine<u32, 4, 4> = EmbeddedStateMachine::new(0);
+
+        let start = sm.add_state("start", noop_state, count_exec).unwrap();
+        let low = sm.add_state("low", noop_state, count_exec).unwrap();
+        let high = sm.add_state("high", noop_state, count_exec).unwrap();
+
+        let low_id = sm.add_transition("low", start, low, 0, always, noop_done).unwrap();
+        let via = sm.add_transition("high", start, high, 3, always, noop_done).unwrap();
+        assert_eq!(sm.transition(via).unwrap().name.as_str(), "high");
+        assert_eq!(sm.transition(low_id).unwrap().name.as_str(), "low");
+        assert_eq!(sm.transition(2).err(), Some(FsmError::TransitionIndexOutOfBounds));
+
+        assert_eq!(sm.run(), EmbeddedOutcome::Errored(FsmError::NoActiveState.into()));
+        sm.set_active_state(start).unwrap();
+        assert_eq!(sm.run(), EmbeddedOutcome::Transitioned { from: start, to: high, via });
+        assert_eq!(sm.run(), EmbeddedOutcome::Stayed(high));
+        assert_eq!(*sm.data(), 2);
+    }
+
+    #[test]
+    fn embedded_machine_is_bounded() {
+        let mut sm: EmbeddedStateMachine<u32, 2, 1, 4> = EmbeddedStateMachine::new(0);
+
+        assert_eq!(sm.add_state("too long", noop_state, noop_state), Err(FsmError::NameTooLong));
+        let a = sm.add_state("a", noop_state, noop_state).unwrap();
+        assert_eq!(sm.add_state("a", noop_state, noop_state), Err(FsmError::DuplicateStateName));
+        let b = sm.add_state("b", noop_state, noop_state).unwrap();
+        assert_eq!(sm.add_state("c", noop_state, noop_state), Err(FsmError::MaxNumberOfStatesExceeded));
+
+        assert_eq!(sm.add_transition("aa", a, a, 0, always, noop_done), Err(FsmError::AddTransitionSrcDstStatesEqual));
+        assert_eq!(sm.add_transition("ax", a, 5, 0, always, noop_done), Err(FsmError::TransitionIndexOutOfBounds));
+        sm.add_transition("ab", a, b, 0, always, noop_done).unwrap();
+        assert_eq!(sm.add_transition("ba", b, a, 0, always, noop_done), Err(FsmError::MaxNumberOfTransitionsExceeded));
+    }
+}
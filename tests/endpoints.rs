@@ -0,0 +1,39 @@
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+
+    fn init_sm() -> StateMachine<u32> {
+        let data = 0;
+
+        declare_data_type!(u32);
+        declare_state_machine!(sm, data, 3);
+
+        new_state!(sm, state1, &State::noop_init, &State::noop_exec);
+        new_state!(sm, state2, &State::noop_init, &State::noop_exec);
+        new_state!(sm, state3, &State::noop_init, &State::noop_exec);
+
+        sm
+    }
+
+    #[test]
+    fn mismatched_endpoints_rejected() {
+        let mut sm = init_sm();
+
+        let t: Transition<u32> = Transition::new("t", 0, 1, always, noop_done);
+        assert_eq!(sm.add_transition(t, 0, 2), Err(FsmError::TransitionEndpointsMismatch));
+        assert_eq!(sm.transition(0, 2).err(), Some(FsmError::TransitionIsEmpty));
+
+        let t: Transition<u32> = Transition::new("t", 0, 1, always, noop_done);
+        assert_eq!(sm.add_self_transition(t, 1), Err(FsmError::TransitionEndpointsMismatch));
+    }
+
+    #[test]
+    fn place_transition_uses_own_endpoints() {
+        let mut sm = init_sm();
+
+        sm.place_transition(Transition::new("forward", 0, 2, always, noop_done)).unwrap();
+        sm.place_transition(Transition::new("again", 2, 2, always, noop_done)).unwrap();
+        assert_eq!(sm.transition(0, 2).unwrap().name, "forward");
+        assert_eq!(sm.transition(2, 2).unwrap().name, "again");
+    }
+}
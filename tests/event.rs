@@ -0,0 +1,36 @@
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+
+    fn init_sm() -> StateMachine<u32> {
+        let data = 0;
+
+        declare_data_type!(u32);
+        declare_state_machine!(sm, data, 3);
+
+        new_state!(sm, closed, &State::noop_init, &State::noop_exec);
+        new_state!(sm, open, &State::noop_init, &State::noop_exec);
+
+        let t: Transition<u32> = Transition::on_event("closed__open", closed, open, "push", always, noop_done);
+        sm.add_transition(t, closed, open).unwrap();
+        let t: Transition<u32> = Transition::on_event("open__closed", open, closed, "pull", always, noop_done);
+        sm.add_transition(t, open, closed).unwrap();
+
+        sm.set_active_state(closed).unwrap();
+        sm
+    }
+
+    #[test]
+    fn event_transitions_ignore_polling() {
+        let mut sm = init_sm();
+        assert_eq!(sm.run(), StepOutcome::Stayed(0));
+    }
+
+    #[test]
+    fn fire_event_matches_transition() {
+        let mut sm = init_sm();
+        assert_eq!(sm.fire_event("pull"), StepOutcome::Stayed(0));
+        assert_eq!(sm.fire_event("push"), StepOutcome::Transitioned { from: 0, to: 1, via: String::from("closed__open") });
+        assert_eq!(sm.fire_event("pull"), StepOutcome::Transitioned { from: 1, to: 0, via: String::from("open__closed") });
+    }
+}
@@ -0,0 +1,46 @@
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+
+    #[derive(Clone)]
+    struct Status {
+        pub events: Vec<&'static str>,
+    }
+
+    fn acquire(_s: &State<Status>, data: &mut Status) -> Result<(), FsmError> {
+        data.events.push("acquire");
+        Ok(())
+    }
+
+    fn work(_s: &State<Status>, data: &mut Status) -> Result<(), FsmError> {
+        data.events.push("work");
+        Ok(())
+    }
+
+    fn release(_s: &State<Status>, data: &mut Status) -> Result<(), FsmError> {
+        data.events.push("release");
+        Ok(())
+    }
+
+    fn finish(_t: &Transition<Status>, data: &mut Status) -> Result<(), FsmError> {
+        data.events.push("done");
+        Ok(())
+    }
+
+    #[test]
+    fn on_exit_runs_after_done() {
+        let status = Status { events: Vec::new() };
+
+        declare_data_type!(Status);
+        declare_state_machine!(sm, status, 2);
+
+        let busy: State<Status> = State::with_exit("busy", acquire, work, release);
+        let busy = sm.add_state(busy).unwrap();
+        new_state!(sm, idle, &State::noop_init, &State::noop_exec);
+        new_transition!(sm, busy, idle, &always, &finish);
+
+        sm.set_active_state(busy).unwrap();
+        sm.run_steps(2);
+        assert_eq!(sm.data().events, vec!["acquire", "work", "done", "release"]);
+    }
+}
@@ -0,0 +1,101 @@
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+
+    fn init_sm() -> StateMachine<u32> {
+        let data = 0;
+
+        declare_data_type!(u32);
+        declare_state_machine!(sm, data, 3);
+
+        new_state!(sm, idle, &State::noop_init, &State::noop_exec);
+        new_state!(sm, busy, &State::noop_init, &State::noop_exec);
+
+        new_transition!(sm, idle, busy, &always, &noop_done);
+        new_transition!(sm, busy, idle, &always, &noop_done);
+
+        sm
+    }
+
+    #[test]
+    fn dot_export() {
+        let mut sm = init_sm();
+        sm.set_active_state(1).unwrap();
+
+        assert_eq!(sm.to_dot(), concat!(
+            "digraph fsm {\n",
+            "    s0 [label=\"idle\"];\n",
+            "    s1 [label=\"busy\", style=filled, fillcolor=lightblue];\n",
+            "    s0 -> s1 [label=\"idle__busy\"];\n",
+            "    s1 -> s0 [label=\"busy__idle\"];\n",
+            "}\n",
+        ));
+    }
+
+    #[test]
+    fn mermaid_export() {
+        let mut sm = init_sm();
+        assert_eq!(sm.to_mermaid(), concat!(
+            "stateDiagram-v2\n",
+            "    state \"idle\" as s0\n",
+            "    state \"busy\" as s1\n",
+            "    s0 --> s1 : idle__busy\n",
+            "    s1 --> s0 : busy__idle\n",
+        ));
+
+        sm.set_active_state(0).unwrap();
+        assert!(sm.to_mermaid().contains("    state \"busy\" as s1\n    [*] --> s0\n"));
+    }
+
+    #[test]
+    fn mermaid_export_aliases_names_with_spaces() {
+        let mut sm = init_sm();
+        sm.rename_state(1, "busy state").unwrap();
+
+        assert!(sm.to_mermaid().contains("    state \"busy state\" as s1\n"));
+        assert!(sm.to_mermaid().contains("    s0 --> s1 : idle__busy\n"));
+    }
+
+    #[test]
+    fn plantuml_export() {
+        let mut sm = init_sm();
+        sm.set_initial(1).unwrap();
+
+        assert_eq!(sm.to_plantuml(), concat!(
+            "@startuml\n",
+            "state \"idle\" as s0\n",
+            "state \"busy\" as s1\n",
+            "[*] --> s1\n",
+            "s0 --> s1 : idle__busy\n",
+            "s1 --> s0 : busy__idle\n",
+            "@enduml\n",
+        ));
+    }
+
+    #[test]
+    fn rust_skeleton() {
+        let mut sm = init_sm();
+        sm.rename_state(1, "Busy Loop").unwrap();
+
+        let rust = sm.generate_rust("generated");
+        assert!(rust.starts_with("mod generated {\n    use call_fsm::{*};\n"));
+        assert!(rust.contains("    fn busy_loop_exec(_s: &State<DataType>, _data: &mut DataType) -> Result<(), FsmError> {\n"));
+        assert!(rust.contains("    fn idle_busy_check(_t: &Transition<DataType>, _data: &DataType) -> bool {\n        true\n    }\n"));
+        assert!(rust.contains("        let busy_loop = sm.add_state(State::new(\"Busy Loop\", busy_loop_init, busy_loop_exec))"));
+        assert!(rust.contains("        sm.place_transition(Transition::new(\"busy__idle\", busy_loop, idle, busy_idle_check, busy_idle_done))"));
+        assert!(rust.ends_with("        sm\n    }\n}\n"));
+    }
+
+    #[test]
+    fn debug_output() {
+        let mut sm = init_sm();
+        sm.set_active_state(0).unwrap();
+
+        assert_eq!(format!("{:?}", sm), concat!(
+            "StateMachine { num_states: 2, states: [(0, \"idle\"), (1, \"busy\")], ",
+            "active_state: Some(0), active_state_initialized: false, ",
+            "transitions: {0: [(1, \"idle__busy\")], 1: [(0, \"busy__idle\")]}, .. }",
+        ));
+        assert_eq!(format!("{:?}", sm.state(0).unwrap()), "State { name: \"idle\", init: <fn>, exec: <fn>, on_exit: None, max_retries: 0 }");
+    }
+}
@@ -0,0 +1,52 @@
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+
+    fn count_exec(_s: &State<u32>, data: &mut u32) -> Result<(), FsmError> {
+        *data += 1;
+        Ok(())
+    }
+
+    fn init_sm() -> StateMachine<u32> {
+        let data = 0;
+
+        declare_data_type!(u32);
+        declare_state_machine!(sm, data, 3);
+
+        new_state!(sm, fetch, &State::noop_init, &count_exec);
+        new_state!(sm, store, &State::noop_init, &count_exec);
+        new_state!(sm, stuck, &State::noop_init, &State::noop_exec);
+
+        new_transition!(sm, fetch, store, &always, &noop_done);
+        new_transition!(sm, store, fetch, &always, &noop_done);
+
+        sm
+    }
+
+    #[test]
+    fn final_state_halts_run() {
+        let mut sm = init_sm();
+        assert!(!sm.is_final(1));
+        sm.mark_final(1).unwrap();
+        assert!(sm.is_final(1));
+        assert_eq!(sm.mark_final(3), Err(FsmError::StateIndexOutOfBounds));
+
+        sm.set_active_state(0).unwrap();
+        assert_eq!(sm.run_steps(5), vec![
+            StepOutcome::Transitioned { from: 0, to: 1, via: String::from("fetch__store") },
+            StepOutcome::Halted,
+        ]);
+        assert_eq!(sm.run(), StepOutcome::Halted);
+        assert_eq!(sm.current_state_index(), Some(1));
+        assert_eq!(*sm.data(), 2);
+    }
+
+    #[test]
+    fn final_states_are_not_dead_ends() {
+        let mut sm = init_sm();
+        assert_eq!(sm.dead_end_states(), vec![2]);
+
+        sm.mark_final(2).unwrap();
+        assert!(sm.dead_end_states().is_empty());
+    }
+}
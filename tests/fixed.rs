@@ -0,0 +1,49 @@
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+
+    fn noop_state(_data: &mut u32) -> Result<(), FsmError> {
+        Ok(())
+    }
+
+    fn count_exec(data: &mut u32) -> Result<(), FsmError> {
+        *data += 1;
+        Ok(())
+    }
+
+    fn exit_state(data: &mut u32) -> Result<(), FsmError> {
+        *data += 10;
+        Ok(())
+    }
+
+    fn always(_data: &u32) -> bool {
+        true
+    }
+
+    #[test]
+    fn fixed_machine_runs() {
+        let mut sm: FixedStateMachine<u32, 8> = FixedStateMachine::new(0);
+
+        let start = sm.add_state(FixedState::with_exit("start", noop_state, count_exec, exit_state)).unwrap();
+        let low = sm.add_state(FixedState::new("low", noop_state, count_exec)).unwrap();
+        let high = sm.add_state(FixedState::new("high", noop_state, count_exec)).unwrap();
+
+        sm.add_transition(FixedTransition::new("low", start, low, always, noop_state), start, low).unwrap();
+        sm.add_transition(FixedTransition::with_priority("high", start, high, 3, always, noop_state), start, high).unwrap();
+
+        sm.set_active_state(start).unwrap();
+        assert_eq!(sm.run(), FixedOutcome::Transitioned { from: start, to: high, via: "high" });
+        assert_eq!(sm.run(), FixedOutcome::Stayed(high));
+        assert_eq!(*sm.data(), 12);
+    }
+
+    #[test]
+    fn fixed_machine_is_bounded() {
+        let mut sm: FixedStateMachine<u32, 2> = FixedStateMachine::new(0);
+
+        sm.add_state(FixedState::new("a", noop_state, noop_state)).unwrap();
+        sm.add_state(FixedState::new("b", noop_state, noop_state)).unwrap();
+        assert_eq!(sm.add_state(FixedState::new("c", noop_state, noop_state)).err(), Some(FsmError::MaxNumberOfStatesExceeded));
+        assert_eq!(sm.add_transition(FixedTransition::new("x", 0, 2, always, noop_state), 0, 2).err(), Some(FsmError::TransitionIndexOutOfBounds));
+    }
+}
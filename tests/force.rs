@@ -0,0 +1,56 @@
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+
+    fn exit_state(_s: &State<u32>, data: &mut u32) -> Result<(), FsmError> {
+        *data += 10;
+        Ok(())
+    }
+
+    fn count_done(_t: &Transition<u32>, data: &mut u32) -> Result<(), FsmError> {
+        *data += 1;
+        Ok(())
+    }
+
+    fn init_sm() -> StateMachine<u32> {
+        let mut sm = StateMachine::new(0, 3);
+        sm.add_state(State::with_exit("idle", State::noop_init, State::noop_exec, exit_state)).unwrap();
+        sm.add_state(State::new("busy", State::noop_init, State::noop_exec)).unwrap();
+        sm.add_state(State::new("failed", State::noop_init, State::noop_exec)).unwrap();
+        sm.add_transition(Transition::new("start", 0, 1, never, count_done), 0, 1).unwrap();
+        sm.set_active_state(0).unwrap();
+        sm
+    }
+
+    #[test]
+    fn force_runs_done_and_exit() {
+        let mut sm = init_sm();
+
+        sm.force_transition(1).unwrap();
+        assert_eq!(sm.current_state_index(), Some(1));
+        assert_eq!(*sm.data(), 11);
+        assert_eq!(sm.history(), Some(0));
+    }
+
+    #[test]
+    fn force_without_transition_jumps() {
+        let mut sm = init_sm();
+
+        sm.force_transition(2).unwrap();
+        assert_eq!(sm.current_state_index(), Some(2));
+        assert_eq!(*sm.data(), 0);
+        assert_eq!(sm.force_transition(3), Err(FsmError::TransitionIndexOutOfBounds.into()));
+    }
+
+    #[test]
+    fn force_notifies_observer() {
+        let mut sm = init_sm();
+        let seen = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let log = seen.clone();
+        sm.set_transition_observer(move |src, dst, via, _data| log.borrow_mut().push((src, dst, String::from(via))));
+
+        sm.force_transition(1).unwrap();
+        sm.force_transition(2).unwrap();
+        assert_eq!(*seen.borrow(), vec![(0, 1, String::from("start"))]);
+    }
+}
@@ -0,0 +1,75 @@
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+
+    #[derive(Clone, Copy)]
+    struct Machine {
+        pub abort: bool,
+    }
+
+    fn abort_requested(_t: &Transition<Machine>, data: &Machine) -> bool {
+        data.abort
+    }
+
+    fn init_sm() -> StateMachine<Machine> {
+        let machine = Machine { abort: false };
+
+        declare_data_type!(Machine);
+        declare_state_machine!(sm, machine, 3);
+
+        new_state!(sm, load, &State::noop_init, &State::noop_exec);
+        new_state!(sm, run, &State::noop_init, &State::noop_exec);
+        new_state!(sm, aborted, &State::noop_init, &State::noop_exec);
+
+        new_transition!(sm, load, run, &always, &noop_done);
+        new_transition!(sm, run, load, &always, &noop_done);
+
+        let abort: Transition<Machine> = Transition::new("abort", 0, aborted, abort_requested, noop_done);
+        sm.add_global_transition(abort, aborted).unwrap();
+
+        sm.set_active_state(load).unwrap();
+        sm
+    }
+
+    #[test]
+    fn global_transition_fires_from_any_state() {
+        let mut sm = init_sm();
+        sm.run();
+        sm.data_mut().abort = true;
+
+        assert_eq!(sm.run(), StepOutcome::Transitioned { from: 1, to: 0, via: String::from("run__load") });
+
+        sm.set_global_transitions_first(true);
+        assert_eq!(sm.run(), StepOutcome::Transitioned { from: 0, to: 2, via: String::from("abort") });
+    }
+
+    #[test]
+    fn global_transition_into_active_state_is_skipped() {
+        let mut sm = init_sm();
+        sm.data_mut().abort = true;
+        sm.set_active_state(2).unwrap();
+
+        assert_eq!(sm.run(), StepOutcome::Stayed(2));
+    }
+
+    #[test]
+    fn global_transition_reenters_state_with_self_transition() {
+        let mut sm = init_sm();
+        let refresh: Transition<Machine> = Transition::new("refresh", 2, 2, never, noop_done);
+        sm.add_self_transition(refresh, 2).unwrap();
+        sm.data_mut().abort = true;
+        sm.set_active_state(2).unwrap();
+
+        assert_eq!(sm.run(), StepOutcome::Transitioned { from: 2, to: 2, via: String::from("abort") });
+    }
+
+    #[test]
+    fn endpoints_must_match() {
+        let mut sm = init_sm();
+        let abort: Transition<Machine> = Transition::new("abort", 0, 1, abort_requested, noop_done);
+        assert_eq!(sm.add_global_transition(abort, 2), Err(FsmError::TransitionEndpointsMismatch));
+
+        let otherwise: Transition<Machine> = Transition::new("otherwise", 0, 2, always, noop_done);
+        assert_eq!(sm.add_default_transition(otherwise, 1), Err(FsmError::TransitionEndpointsMismatch));
+    }
+}
@@ -0,0 +1,43 @@
+#![cfg(feature = "petgraph")]
+
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+    use petgraph::graph::Graph;
+    use std::rc::Rc;
+
+    fn resolve_state(_name: &str) -> Option<StateCallbacks<u32>> {
+        Some((Rc::new(State::noop_init), Rc::new(State::noop_exec)))
+    }
+
+    fn resolve_transition(name: &str) -> Option<TransitionCallbacks<u32>> {
+        match name {
+            "start" | "finish" => Some((Rc::new(always), Rc::new(noop_done))),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn petgraph_round_trip() {
+        let mut graph = Graph::new();
+        let idle = graph.add_node(String::from("idle"));
+        let busy = graph.add_node(String::from("busy"));
+        let done = graph.add_node(String::from("done"));
+        graph.add_edge(idle, busy, String::from("start"));
+        graph.add_edge(busy, done, String::from("finish"));
+
+        let mut sm = StateMachine::from_petgraph(0, &graph, resolve_state, resolve_transition).unwrap();
+        assert_eq!(sm.transition(1, 2).unwrap().name, "finish");
+
+        sm.set_active_state(0).unwrap();
+        assert_eq!(sm.run(), StepOutcome::Transitioned { from: 0, to: 1, via: String::from("start") });
+
+        let exported = sm.to_petgraph();
+        assert_eq!(exported.node_count(), 3);
+        assert_eq!(exported.edge_count(), 2);
+        assert_eq!(exported.node_weights().collect::<Vec<_>>(), vec!["idle", "busy", "done"]);
+
+        graph.add_edge(done, idle, String::from("unknown"));
+        assert_eq!(StateMachine::from_petgraph(0, &graph, resolve_state, resolve_transition).err(), Some(FsmError::UnresolvedName));
+    }
+}
@@ -0,0 +1,73 @@
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+
+    fn count_exec(_s: &State<u32>, data: &mut u32) -> Result<(), FsmError> {
+        *data += 1;
+        Ok(())
+    }
+
+    #[test]
+    fn associated_helpers() {
+        let mut sm: StateMachine<u32> = StateMachine::new(0, 3);
+
+        let counting = sm.add_state(State::exec_only("counting", count_exec)).unwrap();
+        let idle = sm.add_state(State::new("idle", State::noop_init, State::noop_exec)).unwrap();
+        let blocked = sm.add_state(State::exec_only("blocked", State::noop_exec)).unwrap();
+
+        sm.add_transition(Transition::new("block", counting, blocked, Transition::never, noop_done), counting, blocked).unwrap();
+        sm.add_transition(Transition::new("rest", counting, idle, Transition::always, noop_done), counting, idle).unwrap();
+
+        sm.set_active_state(counting).unwrap();
+        assert_eq!(sm.run(), StepOutcome::Transitioned { from: counting.0, to: idle.0, via: String::from("rest") });
+        assert_eq!(sm.run(), StepOutcome::Stayed(idle.0));
+        assert_eq!(*sm.data(), 1);
+    }
+
+    #[test]
+    fn free_guard_helpers() {
+        let data = 0;
+
+        declare_data_type!(u32);
+        declare_state_machine!(sm, data, 3);
+
+        new_state!(sm, start, &State::noop_init, &count_exec);
+        new_state!(sm, blocked, &State::noop_init, &State::noop_exec);
+        new_state!(sm, end, &State::noop_init, &State::noop_exec);
+
+        new_transition!(sm, start, blocked, &call_fsm::never, &call_fsm::noop_done);
+        new_transition!(sm, start, end, &call_fsm::always, &call_fsm::noop_done);
+
+        sm.set_active_state(start).unwrap();
+        assert_eq!(sm.run(), StepOutcome::Transitioned { from: start.0, to: end.0, via: String::from("start__end") });
+    }
+
+    fn is_even(_t: &Transition<u32>, data: &u32) -> bool {
+        data.is_multiple_of(2)
+    }
+
+    fn is_large(_t: &Transition<u32>, data: &u32) -> bool {
+        *data > 3
+    }
+
+    #[test]
+    fn guard_combinators() {
+        let mut sm: StateMachine<u32> = StateMachine::new(0, 2);
+        let t: Transition<u32> = Transition::new("t", 0, 1, always, noop_done);
+
+        assert!(and(is_even, not(is_large))(&t, &2));
+        assert!(!and(is_even, is_large)(&t, &2));
+        assert!(or(is_even, is_large)(&t, &5));
+        assert!(!or(is_even, is_large)(&t, &3));
+
+        let counting = sm.add_state(State::exec_only("counting", count_exec)).unwrap();
+        let done = sm.add_state(State::exec_only("done", State::noop_exec)).unwrap();
+        sm.add_transition(Transition::new("big_even", counting, done, and(is_even, is_large), noop_done), counting, done).unwrap();
+
+        sm.set_active_state(counting).unwrap();
+        sm.run_steps(3);
+        assert_eq!(sm.current_state_id(), Some(counting));
+        assert_eq!(sm.run(), StepOutcome::Transitioned { from: counting.0, to: done.0, via: String::from("big_even") });
+        assert_eq!(*sm.data(), 4);
+    }
+}
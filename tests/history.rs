@@ -0,0 +1,82 @@
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+
+    #[derive(Clone, Copy)]
+    struct Player {
+        pub paused: bool,
+        pub fail: bool,
+    }
+
+    fn maybe_fail(_s: &State<Player>, data: &mut Player) -> Result<(), FsmError> {
+        if data.fail {
+            data.fail = false;
+            Err(FsmError::StateIsEmpty)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn not_paused(_t: &Transition<Player>, data: &Player) -> bool {
+        !data.paused
+    }
+
+    fn paused(_t: &Transition<Player>, data: &Player) -> bool {
+        data.paused
+    }
+
+    fn ignore_error(_e: FsmErrorKind, _data: &mut Player) -> Option<Destination> {
+        None
+    }
+
+    fn back_to_history(_e: FsmErrorKind, _data: &mut Player) -> Option<Destination> {
+        Some(Destination::History)
+    }
+
+    fn init_sm() -> StateMachine<Player> {
+        let player = Player { paused: false, fail: false };
+
+        declare_data_type!(Player);
+        declare_state_machine!(sm, player, 3);
+
+        new_state!(sm, intro, &State::noop_init, &State::noop_exec);
+        new_state!(sm, level, &State::noop_init, &State::noop_exec);
+        new_state!(sm, pause, &State::noop_init, &maybe_fail);
+
+        new_transition!(sm, intro, level, &not_paused, &noop_done);
+        new_transition!(sm, intro, pause, &paused, &noop_done);
+        new_transition!(sm, level, pause, &paused, &noop_done);
+
+        sm.set_error_callbacks(ignore_error, back_to_history);
+        sm
+    }
+
+    #[test]
+    fn transition_to_history_returns_to_previous_state() {
+        let mut sm = init_sm();
+        assert_eq!(sm.transition_to_history(), Err(FsmError::HistoryIsEmpty));
+
+        sm.set_active_state(0).unwrap();
+        sm.run();
+        sm.data_mut().paused = true;
+        sm.run();
+        assert_eq!(sm.current_state_index(), Some(2));
+        assert_eq!(sm.history(), Some(1));
+
+        sm.transition_to_history().unwrap();
+        assert_eq!(sm.current_state_index(), Some(1));
+        assert_eq!(sm.history(), Some(2));
+    }
+
+    #[test]
+    fn error_callback_routes_to_history() {
+        let mut sm = init_sm();
+        sm.set_active_state(0).unwrap();
+        sm.data_mut().paused = true;
+        sm.run();
+
+        sm.data_mut().fail = true;
+        assert_eq!(sm.run(), StepOutcome::Errored(FsmErrorKind::User(FsmError::StateIsEmpty)));
+        assert_eq!(sm.current_state_index(), Some(0));
+    }
+}
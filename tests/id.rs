@@ -0,0 +1,37 @@
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+
+    #[test]
+    fn typed_ids_round_trip() {
+        let mut sm: StateMachine<u32> = StateMachine::new(0, 2);
+
+        let idle = sm.add_state(State::new("idle", State::noop_init, State::noop_exec)).unwrap();
+        let busy = sm.add_state(State::new("busy", State::noop_init, State::noop_exec)).unwrap();
+        assert_eq!(usize::from(busy), 1);
+        assert_eq!(sm.state_id_by_name("busy"), Some(busy));
+        assert_eq!(sm.state(idle).unwrap().name, "idle");
+
+        let start = sm.add_transition(Transition::new("start", idle, busy, always, noop_done), idle, busy).unwrap();
+        assert_eq!(start, TransitionId { src: idle, dst: busy });
+        assert_eq!(<(usize, usize)>::from(start), (0, 1));
+        assert_eq!(sm.transition(start.src, start.dst).unwrap().name, "start");
+
+        sm.set_active_state(idle).unwrap();
+        sm.run();
+        assert_eq!(sm.current_state_id(), Some(busy));
+        assert_eq!(sm.set_active_state(StateId(5)), Err(FsmError::StateIndexOutOfBounds));
+    }
+
+    #[test]
+    fn plain_indices_still_convert() {
+        let mut sm: StateMachine<u32> = StateMachine::new(0, 2);
+        sm.add_states(&["idle", "busy"], State::noop_init, State::noop_exec).unwrap();
+
+        sm.add_transition(Transition::new("start", 0, 1, always, noop_done), 0, 1).unwrap();
+        assert!(sm.contains_transition(StateId(0), 1));
+        sm.set_active_state(0).unwrap();
+        sm.run();
+        assert_eq!(sm.current_state_index(), Some(1));
+    }
+}
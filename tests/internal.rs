@@ -0,0 +1,59 @@
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+
+    #[derive(Clone, Default)]
+    struct Counter {
+        inits: u32,
+        exits: u32,
+        actions: u32,
+    }
+
+    fn count_init(_s: &State<Counter>, data: &mut Counter) -> Result<(), FsmError> {
+        data.inits += 1;
+        Ok(())
+    }
+
+    fn count_exit(_s: &State<Counter>, data: &mut Counter) -> Result<(), FsmError> {
+        data.exits += 1;
+        Ok(())
+    }
+
+    fn count_action(_t: &Transition<Counter>, data: &mut Counter) -> Result<(), FsmError> {
+        data.actions += 1;
+        Ok(())
+    }
+
+    #[test]
+    fn internal_transition_keeps_state() {
+        let mut sm: StateMachine<Counter> = StateMachine::new(Counter::default(), 2);
+        let idle = sm.add_state(State::with_exit("idle", count_init, State::noop_exec, count_exit)).unwrap();
+
+        let tick: Transition<Counter> = Transition::on_event("tick", idle, idle, "tick", always, count_action);
+        sm.add_internal_transition(tick, idle).unwrap();
+        sm.set_active_state(idle).unwrap();
+
+        assert_eq!(sm.run(), StepOutcome::Stayed(idle.0));
+        assert_eq!(sm.fire_event("tick"), StepOutcome::Internal { state: idle.0, via: String::from("tick") });
+        assert_eq!(sm.fire_event("tick"), StepOutcome::Internal { state: idle.0, via: String::from("tick") });
+
+        assert_eq!(sm.data().inits, 1);
+        assert_eq!(sm.data().exits, 0);
+        assert_eq!(sm.data().actions, 2);
+    }
+
+    #[test]
+    fn internal_transition_checked_first() {
+        let mut sm: StateMachine<Counter> = StateMachine::new(Counter::default(), 2);
+        let idle = sm.add_state(State::new("idle", State::noop_init, State::noop_exec)).unwrap();
+        let busy = sm.add_state(State::new("busy", State::noop_init, State::noop_exec)).unwrap();
+
+        sm.add_transition(Transition::new("leave", idle, busy, always, count_action), idle, busy).unwrap();
+        sm.add_internal_transition(Transition::new("stay", idle, idle, always, count_action), idle).unwrap();
+        sm.set_active_state(idle).unwrap();
+
+        assert_eq!(sm.run(), StepOutcome::Internal { state: idle.0, via: String::from("stay") });
+        assert_eq!(sm.add_internal_transition(Transition::new("bad", idle, busy, always, count_action), idle),
+                   Err(FsmError::TransitionEndpointsMismatch));
+    }
+}
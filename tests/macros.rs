@@ -0,0 +1,86 @@
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+
+    #[derive(Clone)]
+    struct Light {
+        pub on: bool,
+    }
+
+    #[derive(Clone)]
+    struct Counter {
+        pub ticks: u32,
+    }
+
+    #[test]
+    fn two_machines_in_one_scope() {
+        let light = Light { on: false };
+        let counter = Counter { ticks: 0 };
+
+        declare_state_machine!(lamp, light, Light, 2);
+        declare_state_machine!(clock, counter, Counter, 2);
+
+        new_state!(lamp, off, State::noop_init, |_s, data| { data.on = false; Ok(()) });
+        new_state!(lamp, on, State::noop_init, |_s, data| { data.on = true; Ok(()) });
+        new_transition!(lamp, off, on, Transition::always, noop_done);
+
+        new_state!(clock, counting, State::noop_init, |_s, data| { data.ticks += 1; Ok(()) });
+        new_state!(clock, stopped, State::noop_init, State::noop_exec);
+        new_transition!(clock, counting, stopped, |_t, data| data.ticks >= 2, noop_done);
+
+        lamp.set_active_state(off).unwrap();
+        clock.set_active_state(counting).unwrap();
+        lamp.run_steps(2);
+        clock.run_steps(3);
+
+        assert!(lamp.data().on);
+        assert_eq!(clock.data().ticks, 2);
+        assert_eq!(clock.current_state_id(), Some(stopped));
+    }
+
+    fn count_exec(_s: &State<Counter>, data: &mut Counter) -> Result<(), FsmError> {
+        data.ticks += 1;
+        Ok(())
+    }
+
+    fn ticks_reached(_t: &Transition<Counter>, data: &Counter) -> bool {
+        data.ticks >= 2
+    }
+
+    #[test]
+    fn whole_machine_in_one_block() {
+        let mut sm = fsm! {
+            data: Counter { ticks: 0 },
+            states {
+                idle: (State::noop_init, count_exec),
+                running: (State::noop_init, count_exec),
+                done: (State::noop_init, State::noop_exec),
+            }
+            transitions {
+                idle -> running: (ticks_reached, noop_done),
+                running -> done: (|_t, data| data.ticks >= 4, noop_done),
+                done -> done: (Transition::never, noop_done),
+            }
+            initial: idle
+        };
+
+        assert_eq!(sm.state_by_name("running"), Some(1));
+        assert_eq!(sm.transition_by_name("done__done"), Some((2, 2)));
+        sm.run_steps(4);
+        assert!(sm.is_in_state("done"));
+        assert_eq!(sm.data().ticks, 4);
+    }
+
+    #[test]
+    fn block_without_initial_state() {
+        let sm = fsm! {
+            data: Light { on: false },
+            states {
+                off: (State::noop_init, State::noop_exec),
+            }
+            transitions {}
+        };
+        assert_eq!(sm.current_state_index(), None);
+        assert_eq!(sm.states_iter().count(), 1);
+    }
+}
@@ -0,0 +1,76 @@
+#![cfg(feature = "metrics")]
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use call_fsm::{*};
+
+    #[test]
+    fn transition_counts_and_time_in_state() {
+        let data = 0;
+
+        declare_data_type!(u32);
+        declare_state_machine!(sm, data, 2);
+
+        new_state!(sm, ping, &State::noop_init, &State::noop_exec);
+        new_state!(sm, pong, &State::noop_init, &State::noop_exec);
+
+        new_transition!(sm, ping, pong, &always, &noop_done);
+        new_transition!(sm, pong, ping, &always, &noop_done);
+
+        let clock = MockClock::new();
+        sm.set_clock(clock.clone());
+
+        sm.set_active_state(ping).unwrap();
+        for _ in 0..5 {
+            if sm.current_state_id() == Some(ping) {
+                clock.advance(Duration::from_millis(10));
+            }
+            sm.run();
+        }
+
+        assert_eq!(sm.transition_count(ping, pong), 3);
+        assert_eq!(sm.transition_count(pong, ping), 2);
+        assert_eq!(sm.transition_count(ping, 5), 0);
+        assert_eq!(sm.time_in_state(ping), Duration::from_millis(30));
+        assert_eq!(sm.time_in_state(pong), Duration::ZERO);
+    }
+
+    #[test]
+    fn time_in_state_with_mock_clock() {
+        let data = 0;
+
+        declare_data_type!(u32);
+        declare_state_machine!(sm, data, 2);
+
+        new_state!(sm, ping, &State::noop_init, &State::noop_exec);
+        new_state!(sm, pong, &State::noop_init, &State::noop_exec);
+
+        new_transition!(sm, ping, pong, &always, &noop_done);
+
+        let clock = MockClock::new();
+        sm.set_clock(clock.clone());
+
+        sm.set_active_state(ping).unwrap();
+        clock.advance(Duration::from_secs(5));
+        sm.run();
+        clock.advance(Duration::from_secs(2));
+
+        assert_eq!(sm.time_in_state(ping), Duration::from_secs(5));
+        assert_eq!(sm.time_in_state(pong), Duration::from_secs(2));
+    }
+
+    #[test]
+    fn forced_transitions_are_counted() {
+        let mut sm: StateMachine<u32> = StateMachine::new(0, 2);
+        sm.add_state(State::new("ping", State::noop_init, State::noop_exec)).unwrap();
+        sm.add_state(State::new("pong", State::noop_init, State::noop_exec)).unwrap();
+        sm.add_transition(Transition::new("serve", 0, 1, always, noop_done), 0, 1).unwrap();
+        sm.set_active_state(0).unwrap();
+
+        sm.force_transition(1).unwrap();
+        sm.force_transition(0).unwrap();
+        assert_eq!(sm.transition_count(0, 1), 1);
+        assert_eq!(sm.transition_count(1, 0), 0);
+    }
+}
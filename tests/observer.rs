@@ -0,0 +1,40 @@
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use call_fsm::{*};
+
+    fn count_done(_t: &Transition<u32>, data: &mut u32) -> Result<(), FsmError> {
+        *data += 1;
+        Ok(())
+    }
+
+    #[test]
+    fn observer_sees_every_transition() {
+        let data = 0;
+
+        declare_data_type!(u32);
+        declare_state_machine!(sm, data, 2);
+
+        new_state!(sm, ping, &State::noop_init, &State::noop_exec);
+        new_state!(sm, pong, &State::noop_init, &State::noop_exec);
+
+        new_transition!(sm, ping, pong, &always, &count_done);
+        let t: Transition<u32> = Transition::new("pong__pong", pong, pong, always, count_done);
+        sm.add_self_transition(t, pong).unwrap();
+
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let log = seen.clone();
+        sm.set_transition_observer(move |src, dst, name, data| {
+            log.borrow_mut().push((src, dst, String::from(name), *data));
+        });
+
+        sm.set_active_state(ping).unwrap();
+        sm.run_steps(2);
+
+        assert_eq!(*seen.borrow(), vec![
+            (0, 1, String::from("ping__pong"), 1),
+            (1, 1, String::from("pong__pong"), 2),
+        ]);
+    }
+}
@@ -0,0 +1,116 @@
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+
+    #[test]
+    fn transitions_evaluated_in_insertion_order() {
+        let data = 0;
+
+        declare_data_type!(u32);
+        declare_state_machine!(sm, data, 3);
+
+        new_state!(sm, start, &State::noop_init, &State::noop_exec);
+        new_state!(sm, low, &State::noop_init, &State::noop_exec);
+        new_state!(sm, high, &State::noop_init, &State::noop_exec);
+
+        new_transition!(sm, start, high, &always, &noop_done);
+        new_transition!(sm, start, low, &always, &noop_done);
+
+        sm.set_active_state(start).unwrap();
+        assert_eq!(sm.run(), StepOutcome::Transitioned { from: start.0, to: high.0, via: String::from("start__high") });
+    }
+
+    #[test]
+    fn higher_priority_transition_wins() {
+        let data = 0;
+
+        declare_data_type!(u32);
+        declare_state_machine!(sm, data, 3);
+
+        new_state!(sm, state1, &State::noop_init, &State::noop_exec);
+        new_state!(sm, state2, &State::noop_init, &State::noop_exec);
+        new_state!(sm, state3, &State::noop_init, &State::noop_exec);
+
+        new_transition!(sm, state1, state2, &always, &noop_done);
+        let urgent: Transition<u32> = Transition::with_priority("urgent", state1, state3, 10, always, noop_done);
+        sm.add_transition(urgent, state1, state3).unwrap();
+
+        sm.set_active_state(state1).unwrap();
+        assert_eq!(sm.run(), StepOutcome::Transitioned { from: state1.0, to: state3.0, via: String::from("urgent") });
+    }
+
+    #[test]
+    fn transition_updated_in_place() {
+        let data = 0;
+
+        declare_data_type!(u32);
+        declare_state_machine!(sm, data, 3);
+
+        new_state!(sm, start, &State::noop_init, &State::noop_exec);
+        new_state!(sm, low, &State::noop_init, &State::noop_exec);
+        new_state!(sm, high, &State::noop_init, &State::noop_exec);
+
+        new_transition!(sm, start, low, &always, &noop_done);
+        new_transition!(sm, start, high, &always, &noop_done);
+
+        assert_eq!(sm.mut_transition(start, 5).err(), Some(FsmError::TransitionIndexOutOfBounds));
+        assert_eq!(sm.mut_transition(high, low).err(), Some(FsmError::TransitionIsEmpty));
+
+        sm.mut_transition(start, low).unwrap().enabled = false;
+        sm.set_active_state(start).unwrap();
+        assert_eq!(sm.run(), StepOutcome::Transitioned { from: start.0, to: high.0, via: String::from("start__high") });
+
+        sm.mut_transition(start, low).unwrap().enabled = true;
+        sm.set_transition_priority(start, high, -1).unwrap();
+        sm.set_active_state(start).unwrap();
+        assert_eq!(sm.run(), StepOutcome::Transitioned { from: start.0, to: low.0, via: String::from("start__low") });
+    }
+
+    #[test]
+    fn transition_endpoints_stay_fixed() {
+        let data = 0;
+
+        declare_data_type!(u32);
+        declare_state_machine!(sm, data, 3);
+
+        new_state!(sm, start, &State::noop_init, &State::noop_exec);
+        new_state!(sm, low, &State::noop_init, &State::noop_exec);
+
+        new_transition!(sm, start, low, &always, &noop_done);
+
+        {
+            let mut transition = sm.mut_transition(start, low).unwrap();
+            transition.dst = 7;
+            assert_eq!(transition.dst, 7);
+        }
+        assert_eq!(sm.transition(start, low).unwrap().dst, low.0);
+        sm.set_active_state(start).unwrap();
+        assert_eq!(sm.run(), StepOutcome::Transitioned { from: start.0, to: low.0, via: String::from("start__low") });
+    }
+
+    fn count_done(_t: &Transition<u32>, data: &mut u32) -> Result<(), FsmError> {
+        *data += 1;
+        Ok(())
+    }
+
+    #[test]
+    fn transition_callbacks_swapped() {
+        let data = 0;
+
+        declare_data_type!(u32);
+        declare_state_machine!(sm, data, 2);
+
+        new_state!(sm, start, &State::noop_init, &State::noop_exec);
+        new_state!(sm, end, &State::noop_init, &State::noop_exec);
+        new_transition!(sm, start, end, &always, &noop_done);
+
+        sm.set_transition_callbacks(start, end, never, count_done).unwrap();
+        sm.set_active_state(start).unwrap();
+        assert_eq!(sm.run(), StepOutcome::Stayed(start.0));
+
+        sm.set_transition_callbacks(start, end, always, count_done).unwrap();
+        assert_eq!(sm.run(), StepOutcome::Transitioned { from: start.0, to: end.0, via: String::from("start__end") });
+        assert_eq!(*sm.data(), 1);
+        assert_eq!(sm.set_transition_callbacks(end, start, always, noop_done), Err(FsmError::TransitionIsEmpty));
+    }
+}
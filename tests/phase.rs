@@ -0,0 +1,135 @@
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+
+    #[derive(Clone)]
+    struct Job {
+        pub fail_init: bool,
+        pub fail_exec: bool,
+        pub phases: Vec<Phase>,
+    }
+
+    fn start(_s: &State<Job>, data: &mut Job) -> Result<(), FsmError> {
+        if data.fail_init { Err(FsmError::StateIsEmpty) } else { Ok(()) }
+    }
+
+    fn work(_s: &State<Job>, data: &mut Job) -> Result<(), FsmError> {
+        if data.fail_exec { Err(FsmError::StateIsEmpty) } else { Ok(()) }
+    }
+
+    fn record_phase(_e: FsmErrorKind, phase: Phase, data: &mut Job) -> Option<Destination> {
+        data.phases.push(phase);
+        None
+    }
+
+    fn route_by_phase(_e: FsmErrorKind, phase: Phase, _data: &mut Job) -> Option<Destination> {
+        match phase {
+            Phase::Init => Some(Destination::Name(String::from("cleanup"))),
+            _ => None,
+        }
+    }
+
+    fn init_sm(fail_init: bool, fail_exec: bool) -> StateMachine<Job> {
+        let job = Job { fail_init, fail_exec, phases: Vec::new() };
+
+        declare_data_type!(Job);
+        declare_state_machine!(sm, job, 2);
+
+        new_state!(sm, working, &start, &work);
+        new_state!(sm, cleanup, &State::noop_init, &State::noop_exec);
+
+        sm.set_error_callbacks_with_phase(record_phase, route_by_phase);
+        sm.set_active_state(working).unwrap();
+        sm
+    }
+
+    #[test]
+    fn init_error_routes_to_cleanup() {
+        let mut sm = init_sm(true, false);
+        sm.run();
+        assert_eq!(sm.data().phases, vec![Phase::Init]);
+        assert!(sm.is_in_state("cleanup"));
+    }
+
+    #[test]
+    fn exec_error_stays_for_retry() {
+        let mut sm = init_sm(false, true);
+        sm.run();
+        assert_eq!(sm.data().phases, vec![Phase::Exec]);
+        assert!(sm.is_in_state("working"));
+    }
+
+    fn failing_done(_t: &Transition<Job>, _data: &mut Job) -> Result<(), FsmError> {
+        Err(FsmError::TransitionIsEmpty)
+    }
+
+    #[test]
+    fn error_context_identifies_failing_transition() {
+        let job = Job { fail_init: false, fail_exec: false, phases: Vec::new() };
+
+        declare_data_type!(Job);
+        declare_state_machine!(sm, job, 2);
+
+        new_state!(sm, working, &start, &work);
+        new_state!(sm, cleanup, &State::noop_init, &State::noop_exec);
+        new_transition!(sm, working, cleanup, &always, &failing_done);
+
+        sm.set_error_callbacks_ctx(
+            |_e, _context, _data: &mut Job| None,
+            |_e, context, _data: &mut Job| {
+                assert_eq!(context, ErrorContext { phase: Phase::TransitionDone, state: 0, transition: Some((0, 1)), previous_state: None });
+                Some(Destination::Index(context.transition.unwrap().1))
+            });
+        sm.set_active_state(working).unwrap();
+
+        assert_eq!(sm.run(), StepOutcome::Errored(FsmErrorKind::User(FsmError::TransitionIsEmpty)));
+        assert!(sm.is_in_state("cleanup"));
+    }
+
+    #[test]
+    fn error_context_carries_previous_state() {
+        let job = Job { fail_init: false, fail_exec: false, phases: Vec::new() };
+
+        declare_data_type!(Job);
+        declare_state_machine!(sm, job, 2);
+
+        new_state!(sm, idle, &State::noop_init, &State::noop_exec);
+        new_state!(sm, working, &start, &work);
+        new_transition!(sm, idle, working, &always, &noop_done);
+
+        sm.set_error_callbacks_ctx(
+            |_e, _context, _data: &mut Job| None,
+            |_e, context, data: &mut Job| {
+                assert_eq!(context.previous_state, Some(0));
+                data.phases.push(context.phase);
+                None
+            });
+        sm.set_active_state(idle).unwrap();
+        sm.run();
+        assert_eq!(sm.previous_state(), Some(0));
+
+        sm.data_mut().fail_exec = true;
+        sm.run();
+        assert_eq!(sm.data().phases, vec![Phase::Exec]);
+    }
+
+    struct SharedBuffer(std::rc::Rc<std::cell::RefCell<String>>);
+
+    impl std::fmt::Write for SharedBuffer {
+        fn write_str(&mut self, s: &str) -> std::fmt::Result {
+            self.0.borrow_mut().push_str(s);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn error_writer_captures_errors() {
+        let mut sm = init_sm(false, true);
+        let buffer = std::rc::Rc::new(std::cell::RefCell::new(String::new()));
+        sm.set_error_writer(Box::new(SharedBuffer(buffer.clone())));
+
+        sm.run();
+        assert!(buffer.borrow().starts_with("Error state: User(StateIsEmpty) (ErrorContext { phase: Exec"));
+        assert_eq!(buffer.borrow().lines().count(), 1);
+    }
+}
@@ -0,0 +1,89 @@
+#![cfg(feature = "rand")]
+
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn init_sm() -> StateMachine<u32> {
+        let data = 0;
+
+        declare_data_type!(u32);
+        declare_state_machine!(sm, data, 3);
+
+        new_state!(sm, start, &State::noop_init, &State::noop_exec);
+        new_state!(sm, rare, &State::noop_init, &State::noop_exec);
+        new_state!(sm, common, &State::noop_init, &State::noop_exec);
+
+        new_transition!(sm, start, rare, &always, &noop_done);
+        new_transition!(sm, start, common, &always, &noop_done);
+        sm.mut_transition(start, common).unwrap().weight = 3.0;
+
+        sm
+    }
+
+    fn sample(sm: &mut StateMachine<u32>, runs: usize) -> Vec<usize> {
+        (0..runs).map(|_| {
+            sm.set_active_state(0).unwrap();
+            sm.run();
+            sm.current_state_index().unwrap()
+        }).collect()
+    }
+
+    #[test]
+    fn first_passing_wins_without_random_mode() {
+        let mut sm = init_sm();
+        sm.set_rng(StdRng::seed_from_u64(7));
+
+        assert!(sample(&mut sm, 20).iter().all(|&index| index == 1));
+    }
+
+    #[test]
+    fn weighted_and_reproducible() {
+        let mut sm = init_sm();
+        sm.set_random_selection(true);
+        sm.set_rng(StdRng::seed_from_u64(7));
+        let first = sample(&mut sm, 400);
+
+        sm.set_rng(StdRng::seed_from_u64(7));
+        assert_eq!(sample(&mut sm, 400), first);
+
+        let common = first.iter().filter(|&&index| index == 2).count();
+        assert!((250..350).contains(&common), "common fired {} times", common);
+    }
+
+    #[test]
+    fn zero_weight_never_fires() {
+        let mut sm = init_sm();
+        sm.mut_transition(0, 2).unwrap().weight = 0.0;
+        sm.set_random_selection(true);
+        sm.set_rng(StdRng::seed_from_u64(1));
+
+        assert!(sample(&mut sm, 50).iter().all(|&index| index == 1));
+    }
+
+    #[test]
+    fn peek_leaves_rng_untouched() {
+        let mut sm = init_sm();
+        sm.set_random_selection(true);
+        sm.set_rng(StdRng::seed_from_u64(7));
+        let first = sample(&mut sm, 50);
+
+        sm.set_rng(StdRng::seed_from_u64(7));
+        sm.set_active_state(0).unwrap();
+        for _ in 0..10 {
+            assert_eq!(sm.peek_transition(), Some((1, "start__rare")));
+        }
+        assert_eq!(sample(&mut sm, 50), first);
+    }
+
+    #[test]
+    fn candidates_list_passing_transitions() {
+        let mut sm = init_sm();
+        assert!(sm.candidate_transitions().is_empty());
+
+        sm.set_active_state(0).unwrap();
+        assert_eq!(sm.candidate_transitions(), vec![(1, "start__rare", 1.0), (2, "start__common", 3.0)]);
+    }
+}
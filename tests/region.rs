@@ -0,0 +1,175 @@
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+
+    #[derive(Clone)]
+    struct Device {
+        pub powered: bool,
+        pub connected: bool,
+    }
+
+    fn power_on(_s: &State<Device>, data: &mut Device) -> Result<(), FsmError> {
+        data.powered = true;
+        Ok(())
+    }
+
+    fn connect(_s: &State<Device>, data: &mut Device) -> Result<(), FsmError> {
+        data.connected = true;
+        Ok(())
+    }
+
+    fn is_powered(_t: &Transition<Device>, data: &Device) -> bool {
+        data.powered
+    }
+
+    fn init_sm() -> StateMachine<Device> {
+        let device = Device { powered: false, connected: false };
+
+        declare_data_type!(Device);
+        declare_state_machine!(sm, device, 4);
+
+        new_state!(sm, off, &State::noop_init, &State::noop_exec);
+        new_state!(sm, on, &power_on, &State::noop_exec);
+        new_state!(sm, offline, &State::noop_init, &State::noop_exec);
+        new_state!(sm, online, &connect, &State::noop_exec);
+
+        new_transition!(sm, off, on, &always, &noop_done);
+        new_transition!(sm, offline, online, &is_powered, &noop_done);
+
+        sm
+    }
+
+    #[test]
+    fn regions_step_independently() {
+        let mut sm = init_sm();
+        let power = sm.add_region(&[0, 1]).unwrap();
+        let network = sm.add_region(&[2, 3]).unwrap();
+        sm.set_region_active(power, 0).unwrap();
+        sm.set_region_active(network, 2).unwrap();
+
+        assert_eq!(sm.run_regions(), vec![
+            StepOutcome::Transitioned { from: 0, to: 1, via: String::from("off__on") },
+            StepOutcome::Stayed(2),
+        ]);
+        assert_eq!(sm.run_regions(), vec![
+            StepOutcome::Stayed(1),
+            StepOutcome::Transitioned { from: 2, to: 3, via: String::from("offline__online") },
+        ]);
+        sm.run_regions();
+
+        assert_eq!(sm.region_state(power), Some(1));
+        assert_eq!(sm.region_state(network), Some(3));
+        assert!(sm.data().powered && sm.data().connected);
+        assert_eq!(sm.current_state_index(), None);
+    }
+
+    #[test]
+    fn region_validation() {
+        let mut sm = init_sm();
+        let power = sm.add_region(&[0, 1]).unwrap();
+
+        assert_eq!(sm.add_region(&[1, 2]), Err(FsmError::RegionOverlap));
+        assert_eq!(sm.set_region_active(power, 2), Err(FsmError::StateNotInRegion));
+        assert_eq!(sm.set_region_active(5, 0), Err(FsmError::RegionIndexOutOfBounds));
+    }
+
+    #[test]
+    fn transitions_stay_inside_regions() {
+        let mut sm = init_sm();
+        let power = sm.add_region(&[0, 1]).unwrap();
+        sm.add_region(&[2, 3]).unwrap();
+
+        let t = Transition::new("on__offline", 1, 2, always, noop_done);
+        assert_eq!(sm.add_transition(t, 1, 2), Err(FsmError::TransitionLeavesRegion));
+        let t = Transition::new("otherwise", 1, 3, always, noop_done);
+        assert_eq!(sm.add_default_transition(t, 1), Err(FsmError::TransitionLeavesRegion));
+        let t = Transition::new("reset", 0, 0, always, noop_done);
+        assert_eq!(sm.add_global_transition(t, 0), Err(FsmError::TransitionLeavesRegion));
+        assert_eq!(sm.add_transitions(&[(1, 0, "on__off"), (3, 1, "online__on")], always, noop_done),
+                   Err((1, FsmError::TransitionLeavesRegion)));
+        assert!(!sm.contains_transition(1, 0));
+
+        sm.add_transition(Transition::new("on__off", 1, 0, always, noop_done), 1, 0).unwrap();
+        sm.set_region_active(power, 1).unwrap();
+        assert_eq!(sm.region_state(power), Some(1));
+    }
+
+    #[test]
+    fn region_rejects_transitions_leading_out() {
+        let mut sm = init_sm();
+        sm.add_transition(Transition::new("on__offline", 1, 2, always, noop_done), 1, 2).unwrap();
+
+        assert_eq!(sm.add_region(&[0, 1]), Err(FsmError::TransitionLeavesRegion));
+        assert_eq!(sm.add_region(&[0, 1, 2, 3]).unwrap(), 0);
+    }
+
+    #[test]
+    fn region_rejects_transitions_leading_in() {
+        let mut sm = init_sm();
+
+        assert_eq!(sm.add_region(&[1, 2]), Err(FsmError::TransitionLeavesRegion));
+        assert_eq!(sm.add_region(&[2, 3]).unwrap(), 0);
+        assert_eq!(sm.add_region(&[0, 1]).unwrap(), 1);
+    }
+
+    fn failing(_s: &State<Device>, _data: &mut Device) -> Result<(), FsmError> {
+        Err(FsmError::StateIsEmpty)
+    }
+
+    #[test]
+    fn error_routes_stay_inside_regions() {
+        let mut sm = init_sm();
+        sm.set_state_callbacks(1, State::noop_init, failing).unwrap();
+        let power = sm.add_region(&[0, 1]).unwrap();
+        let network = sm.add_region(&[2, 3]).unwrap();
+        sm.set_region_active(power, 1).unwrap();
+        sm.set_region_active(network, 2).unwrap();
+
+        sm.set_error_callbacks(|_e, _data| None, |_e, _data| Some(Destination::Name(String::from("offline"))));
+        sm.run_regions();
+        assert_eq!(sm.region_state(power), Some(1));
+
+        sm.set_error_callbacks(|_e, _data| None, |_e, _data| Some(Destination::Index(0)));
+        sm.run_regions();
+        assert_eq!(sm.region_state(power), Some(0));
+        assert_eq!(sm.region_state(network), Some(2));
+    }
+
+    #[test]
+    fn one_pass_uses_one_unit_of_fuel() {
+        let mut sm = init_sm();
+        let power = sm.add_region(&[0, 1]).unwrap();
+        let network = sm.add_region(&[2, 3]).unwrap();
+        sm.set_region_active(power, 0).unwrap();
+        sm.set_region_active(network, 2).unwrap();
+        sm.set_fuel(1);
+
+        assert!(sm.run_regions().iter().all(|outcome| !matches!(outcome, StepOutcome::Errored(_))));
+        assert_eq!(sm.fuel(), Some(0));
+        assert_eq!(sm.run_regions(), vec![
+            StepOutcome::Errored(FsmError::OutOfFuel.into()),
+            StepOutcome::Errored(FsmError::OutOfFuel.into()),
+        ]);
+        assert_eq!(sm.region_state(power), Some(1));
+    }
+
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn regions_keep_their_own_time_in_state() {
+        use std::time::Duration;
+
+        let mut sm = init_sm();
+        let clock = MockClock::new();
+        sm.set_clock(clock.clone());
+        let power = sm.add_region(&[0, 1]).unwrap();
+        let network = sm.add_region(&[2, 3]).unwrap();
+        sm.set_region_active(power, 0).unwrap();
+        clock.advance(Duration::from_secs(1));
+        sm.set_region_active(network, 2).unwrap();
+        clock.advance(Duration::from_secs(2));
+
+        sm.run_regions();
+        assert_eq!(sm.time_in_state(0), Duration::from_secs(3));
+        assert_eq!(sm.time_in_state(2), Duration::ZERO);
+    }
+}
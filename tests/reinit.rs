@@ -0,0 +1,64 @@
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+
+    #[derive(Clone)]
+    struct Status {
+        pub events: Vec<&'static str>,
+    }
+
+    fn acquire(_s: &State<Status>, data: &mut Status) -> Result<(), FsmError> {
+        data.events.push("acquire");
+        Ok(())
+    }
+
+    fn work(_s: &State<Status>, data: &mut Status) -> Result<(), FsmError> {
+        data.events.push("work");
+        Ok(())
+    }
+
+    #[test]
+    fn reinitialize_runs_init_again() {
+        let status = Status { events: Vec::new() };
+
+        declare_data_type!(Status);
+        declare_state_machine!(sm, status, 1);
+
+        new_state!(sm, busy, &acquire, &work);
+
+        sm.set_active_state(busy).unwrap();
+        assert!(!sm.is_current_initialized());
+        sm.run();
+        assert!(sm.is_current_initialized());
+
+        sm.reinitialize_current();
+        assert!(!sm.is_current_initialized());
+        sm.run();
+        assert_eq!(sm.data().events, vec!["acquire", "work", "acquire", "work"]);
+        assert_eq!(sm.current_state_id(), Some(busy));
+    }
+
+    fn maintenance(_s: &State<Status>, data: &mut Status) -> Result<(), FsmError> {
+        data.events.push("maintenance");
+        Ok(())
+    }
+
+    #[test]
+    fn swapped_callbacks_apply_on_next_run() {
+        let status = Status { events: Vec::new() };
+
+        declare_data_type!(Status);
+        declare_state_machine!(sm, status, 1);
+
+        new_state!(sm, busy, &acquire, &work);
+
+        sm.set_active_state(busy).unwrap();
+        sm.run();
+        sm.set_state_callbacks(busy, acquire, maintenance).unwrap();
+        sm.run();
+        sm.reinitialize_current();
+        sm.run();
+        assert_eq!(sm.data().events, vec!["acquire", "work", "maintenance", "acquire", "maintenance"]);
+        assert_eq!(sm.set_state_callbacks(3, acquire, work), Err(FsmError::StateIndexOutOfBounds));
+    }
+}
@@ -0,0 +1,99 @@
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+
+    fn init_sm() -> StateMachine<u32> {
+        let data = 0;
+
+        declare_data_type!(u32);
+        declare_state_machine!(sm, data, 3);
+
+        new_state!(sm, state1, &State::noop_init, &State::noop_exec);
+        new_state!(sm, state2, &State::noop_init, &State::noop_exec);
+        new_state!(sm, state3, &State::noop_init, &State::noop_exec);
+
+        new_transition!(sm, state1, state2, &always, &noop_done);
+        new_transition!(sm, state2, state3, &always, &noop_done);
+        new_transition!(sm, state3, state1, &always, &noop_done);
+
+        sm
+    }
+
+    #[test]
+    fn contains_checks() {
+        let mut sm = init_sm();
+
+        assert!(sm.contains_state(1));
+        assert!(!sm.contains_state(3));
+        assert!(sm.contains_transition(0, 1));
+        assert!(!sm.contains_transition(1, 0));
+        assert!(!sm.contains_transition(0, 9));
+
+        sm.remove_state(1).unwrap();
+        assert!(!sm.contains_state(1));
+        assert!(!sm.contains_transition(0, 1));
+    }
+
+    #[test]
+    fn remove_state_clears_transitions() {
+        let mut sm = init_sm();
+
+        sm.remove_state(1).unwrap();
+        assert_eq!(sm.state(1).err(), Some(FsmError::StateIsEmpty));
+        assert_eq!(sm.transition(0, 1).err(), Some(FsmError::TransitionIsEmpty));
+        assert_eq!(sm.transition(1, 2).err(), Some(FsmError::TransitionIsEmpty));
+        assert!(sm.transition(2, 0).is_ok());
+        assert_eq!(sm.state(2).unwrap().name, "state3");
+
+        assert_eq!(sm.remove_state(3), Err(FsmError::StateIndexOutOfBounds));
+    }
+
+    #[test]
+    fn remove_active_state_clears_active() {
+        let mut sm = init_sm();
+        sm.set_active_state(0).unwrap();
+
+        sm.remove_state(0).unwrap();
+        assert_eq!(sm.run(), StepOutcome::Errored(FsmError::NoActiveState.into()));
+    }
+
+    #[test]
+    fn remove_transition_disables_path() {
+        let mut sm = init_sm();
+        sm.set_active_state(0).unwrap();
+
+        sm.remove_transition(0, 1).unwrap();
+        assert_eq!(sm.remove_transition(0, 1), Err(FsmError::TransitionIsEmpty));
+        assert_eq!(sm.remove_transition(0, 3), Err(FsmError::TransitionIndexOutOfBounds));
+        assert_eq!(sm.run(), StepOutcome::Stayed(0));
+    }
+
+    #[test]
+    fn transitions_into_removed_states_are_rejected() {
+        let mut sm = init_sm();
+        sm.remove_state(2).unwrap();
+
+        let t = Transition::new("state1__state3", 0, 2, always, noop_done);
+        assert_eq!(sm.add_transition(t, 0, 2), Err(FsmError::StateIsEmpty));
+        let t = Transition::new("state3__state3", 2, 2, always, noop_done);
+        assert_eq!(sm.add_self_transition(t, 2), Err(FsmError::StateIsEmpty));
+        let t = Transition::new("otherwise", 0, 2, always, noop_done);
+        assert_eq!(sm.add_default_transition(t, 0), Err(FsmError::StateIsEmpty));
+        let t = Transition::new("reset", 0, 2, always, noop_done);
+        assert_eq!(sm.add_global_transition(t, 2), Err(FsmError::StateIsEmpty));
+        assert_eq!(sm.add_transitions(&[(1, 0, "back"), (1, 2, "on")], always, noop_done),
+                   Err((1, FsmError::StateIsEmpty)));
+
+        sm.set_active_state(0).unwrap();
+        assert!(matches!(sm.run(), StepOutcome::Transitioned { from: 0, to: 1, .. }));
+        assert_eq!(sm.run(), StepOutcome::Stayed(1));
+    }
+
+    #[test]
+    fn add_state_bounded_by_max_states() {
+        let mut sm = init_sm();
+
+        let extra: State<u32> = State::new("extra", State::noop_init, State::noop_exec);
+        assert_eq!(sm.add_state(extra).err(), Some(FsmError::MaxNumberOfStatesExceeded));
+    }
+}
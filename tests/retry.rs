@@ -0,0 +1,49 @@
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+
+    #[derive(Clone)]
+    struct Flaky {
+        pub failures_left: u32,
+        pub attempts: u32,
+    }
+
+    fn flaky_exec(_s: &State<Flaky>, data: &mut Flaky) -> Result<(), FsmError> {
+        data.attempts += 1;
+        if data.failures_left > 0 {
+            data.failures_left -= 1;
+            Err(FsmError::StateIsEmpty)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn init_sm(failures: u32, max_retries: u32) -> StateMachine<Flaky> {
+        let mut sm = StateMachine::new(Flaky { failures_left: failures, attempts: 0 }, 1);
+        let state = State { max_retries, ..State::new("flaky", State::noop_init, flaky_exec) };
+        sm.add_state(state).unwrap();
+        sm.set_active_state(0).unwrap();
+        sm
+    }
+
+    #[test]
+    fn transient_failure_retried() {
+        let mut sm = init_sm(2, 2);
+
+        assert_eq!(sm.run(), StepOutcome::Stayed(0));
+        assert_eq!(sm.data().attempts, 3);
+        assert_eq!(sm.retry_count(), 2);
+    }
+
+    #[test]
+    fn retries_exhausted_escalate() {
+        let mut sm = init_sm(3, 2);
+
+        assert_eq!(sm.run(), StepOutcome::Errored(FsmErrorKind::User(FsmError::StateIsEmpty)));
+        assert_eq!(sm.data().attempts, 3);
+
+        sm.set_active_state(0).unwrap();
+        assert_eq!(sm.retry_count(), 0);
+        assert_eq!(sm.run(), StepOutcome::Stayed(0));
+    }
+}
@@ -0,0 +1,90 @@
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+
+    #[derive(Clone, Copy)]
+    struct Counter {
+        pub inits: u32,
+        pub execs: u32,
+        pub refreshes: u32,
+    }
+
+    fn count_init(_s: &State<Counter>, data: &mut Counter) -> Result<(), FsmError> {
+        data.inits += 1;
+        Ok(())
+    }
+
+    fn count_exec(_s: &State<Counter>, data: &mut Counter) -> Result<(), FsmError> {
+        data.execs += 1;
+        Ok(())
+    }
+
+    fn every_other_exec(_t: &Transition<Counter>, data: &Counter) -> bool {
+        data.execs.is_multiple_of(2)
+    }
+
+    fn count_refresh(_t: &Transition<Counter>, data: &mut Counter) -> Result<(), FsmError> {
+        data.refreshes += 1;
+        Ok(())
+    }
+
+    #[test]
+    fn self_transition_reruns_init() {
+        let counter = Counter { inits: 0, execs: 0, refreshes: 0 };
+
+        declare_data_type!(Counter);
+        declare_state_machine!(sm, counter, 1);
+
+        new_state!(sm, polling, &count_init, &count_exec);
+
+        let refresh: Transition<Counter> = Transition::new("refresh", polling, polling, every_other_exec, count_refresh);
+        assert_eq!(sm.add_transition(refresh, polling, polling), Err(FsmError::AddTransitionSrcDstStatesEqual));
+
+        let refresh: Transition<Counter> = Transition::new("refresh", polling, polling, every_other_exec, count_refresh);
+        sm.add_self_transition(refresh, polling).unwrap();
+        sm.set_active_state(polling).unwrap();
+
+        assert_eq!(sm.run(), StepOutcome::Stayed(0));
+        assert_eq!(sm.run(), StepOutcome::Transitioned { from: 0, to: 0, via: String::from("refresh") });
+        assert_eq!(sm.run(), StepOutcome::Stayed(0));
+
+        assert_eq!(sm.data().inits, 2);
+        assert_eq!(sm.data().execs, 3);
+        assert_eq!(sm.data().refreshes, 1);
+    }
+
+    #[test]
+    fn run_until_stable_is_capped() {
+        let counter = Counter { inits: 0, execs: 0, refreshes: 0 };
+
+        declare_data_type!(Counter);
+        declare_state_machine!(sm, counter, 1);
+
+        new_state!(sm, polling, &count_init, &count_exec);
+
+        let refresh: Transition<Counter> = Transition::new("refresh", polling, polling, always, count_refresh);
+        sm.add_self_transition(refresh, polling).unwrap();
+        sm.set_active_state(polling).unwrap();
+
+        assert_eq!(sm.run_until_stable(4), Err(FsmErrorKind::Structural(FsmError::MaxStepsExceeded)));
+        assert_eq!(sm.data().refreshes, 4);
+    }
+
+    #[test]
+    fn macro_self_loop() {
+        let counter = Counter { inits: 0, execs: 0, refreshes: 0 };
+
+        declare_data_type!(Counter);
+        declare_state_machine!(sm, counter, 1);
+
+        new_state!(sm, polling, &count_init, &count_exec);
+        new_transition!(sm, polling, polling, &every_other_exec, &count_refresh);
+        sm.set_active_state(polling).unwrap();
+
+        assert_eq!(sm.run(), StepOutcome::Stayed(0));
+        assert_eq!(sm.run(), StepOutcome::Transitioned { from: 0, to: 0, via: String::from("polling__polling") });
+        assert_eq!(sm.data().inits, 1);
+        sm.run();
+        assert_eq!(sm.data().inits, 2);
+    }
+}
@@ -59,7 +59,7 @@ mod tests {
             &generic_trans_done
         );
 
-        sm.set_error_callbacks(&error_init, &error_exec);
+        sm.set_error_callbacks(error_init, error_exec);
 
         sm
     }
@@ -93,12 +93,12 @@ mod tests {
         Ok(())
     }
 
-    fn error_init(error: FsmError, data: &mut Status) -> Option<Destination> {
+    fn error_init(error: FsmErrorKind, data: &mut Status) -> Option<Destination> {
         println!("{} ::: error init {} ::: {}", now(), error, data.st_u32);
         None
     }
 
-    fn error_exec(error: FsmError, data: &mut Status) -> Option<Destination> {
+    fn error_exec(error: FsmErrorKind, data: &mut Status) -> Option<Destination> {
         println!("{} ::: error exec {} ::: {}", now(), error, data.st_u32);
         Some(Destination::Name(String::from("state2")))
     }
@@ -108,8 +108,9 @@ mod tests {
         let mut sm = init_sm();
         sm.set_active_state(0).unwrap();
 
-        loop {
-            sm.run();
-        }
+        let outcomes = sm.run_steps(3);
+        assert_eq!(outcomes.len(), 3);
+        assert!(outcomes.iter().all(|o| matches!(o, StepOutcome::Transitioned { .. })));
+        assert_eq!(sm.current_state_index(), Some(0));
     }
 }
\ No newline at end of file
@@ -0,0 +1,46 @@
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+
+    fn count_exec(_s: &State<u32>, data: &mut u32) -> Result<(), FsmError> {
+        *data += 1;
+        Ok(())
+    }
+
+    fn init_sm() -> StateMachine<u32> {
+        let data = 0;
+
+        declare_data_type!(u32);
+        declare_state_machine!(sm, data, 3);
+
+        new_state!(sm, idle, &State::noop_init, &count_exec);
+        new_state!(sm, busy, &State::noop_init, &count_exec);
+        new_state!(sm, done, &State::noop_init, &count_exec);
+
+        new_transition!(sm, idle, busy, &always, &noop_done);
+        new_transition!(sm, busy, done, &always, &noop_done);
+
+        sm.set_active_state(idle).unwrap();
+        sm
+    }
+
+    #[test]
+    fn restore_rolls_back_steps() {
+        let mut sm = init_sm();
+        sm.run();
+
+        let snapshot = sm.snapshot();
+        assert_eq!(snapshot.active_state, Some(1));
+        assert_eq!(snapshot.data, 1);
+
+        sm.run();
+        sm.run();
+        assert_eq!(sm.current_state_index(), Some(2));
+
+        sm.restore(snapshot);
+        assert_eq!(sm.current_state_index(), Some(1));
+        assert_eq!(*sm.data(), 1);
+        assert_eq!(sm.history(), Some(0));
+        assert_eq!(sm.run(), StepOutcome::Transitioned { from: 1, to: 2, via: String::from("busy__done") });
+    }
+}
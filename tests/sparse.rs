@@ -0,0 +1,48 @@
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+
+    fn init_sm() -> StateMachine<u32> {
+        let mut sm: StateMachine<u32> = StateMachine::new_sparse(0, 1000);
+        for index in 0..1000 {
+            sm.add_state(State::new(format!("state{}", index), State::noop_init, State::noop_exec)).unwrap();
+        }
+        sm
+    }
+
+    #[test]
+    fn sparse_machine_runs_like_dense() {
+        let mut sm = init_sm();
+
+        sm.add_transition(Transition::new("low", 0, 1, always, noop_done), 0, 1).unwrap();
+        sm.add_transition(Transition::with_priority("high", 0, 999, 5, always, noop_done), 0, 999).unwrap();
+
+        sm.set_active_state(0).unwrap();
+        assert_eq!(sm.run(), StepOutcome::Transitioned { from: 0, to: 999, via: String::from("high") });
+        assert_eq!(sm.transition(0, 1).unwrap().name, "low");
+        assert_eq!(sm.transitions_iter().count(), 2);
+    }
+
+    #[test]
+    fn sparse_removal() {
+        let mut sm = init_sm();
+
+        sm.add_transition(Transition::new("a", 0, 1, always, noop_done), 0, 1).unwrap();
+        sm.add_transition(Transition::new("b", 1, 2, always, noop_done), 1, 2).unwrap();
+        sm.add_transition(Transition::new("c", 2, 1, always, noop_done), 2, 1).unwrap();
+
+        sm.remove_transition(0, 1).unwrap();
+        assert_eq!(sm.transition(0, 1).err(), Some(FsmError::TransitionIsEmpty));
+
+        sm.remove_state(1).unwrap();
+        assert_eq!(sm.transitions_iter().count(), 0);
+        assert_eq!(sm.dead_end_states().len(), 999);
+    }
+
+    #[test]
+    fn sparse_has_no_transition_rows() {
+        let sm = init_sm();
+
+        assert_eq!(sm.active_transitions(0).err(), Some(FsmError::UnsupportedBySparseStorage));
+    }
+}
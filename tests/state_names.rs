@@ -0,0 +1,98 @@
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+
+    fn init_sm() -> StateMachine<u32> {
+        let data = 0;
+
+        declare_data_type!(u32);
+        declare_state_machine!(sm, data, 3);
+
+        new_state!(sm, idle, &State::noop_init, &State::noop_exec);
+        new_state!(sm, busy, &State::noop_init, &State::noop_exec);
+        new_state!(sm, done, &State::noop_init, &State::noop_exec);
+
+        sm
+    }
+
+    #[test]
+    fn lookup_by_name() {
+        let sm = init_sm();
+
+        assert_eq!(sm.state_by_name("idle"), Some(0));
+        assert_eq!(sm.state_by_name("done"), Some(2));
+        assert_eq!(sm.state_by_name("missing"), None);
+    }
+
+    #[test]
+    fn lookup_after_removal() {
+        let mut sm = init_sm();
+
+        sm.remove_state(1).unwrap();
+        assert_eq!(sm.state_by_name("busy"), None);
+        assert_eq!(sm.state_by_name("done"), Some(2));
+    }
+
+    #[test]
+    fn lookup_after_rename() {
+        let mut sm = init_sm();
+
+        sm.mut_state(1).unwrap().name = String::from("working");
+        assert_eq!(sm.state_by_name("busy"), None);
+        assert_eq!(sm.state_by_name("working"), Some(1));
+
+        sm.remove_state(1).unwrap();
+        assert_eq!(sm.state_by_name("working"), None);
+    }
+
+    #[test]
+    fn rename_to_taken_name_is_undone() {
+        let mut sm = init_sm();
+
+        sm.mut_state(1).unwrap().name = String::from("idle");
+        assert_eq!(sm.state(1).unwrap().name, "busy");
+        assert_eq!(sm.state_by_name("idle"), Some(0));
+        assert_eq!(sm.state_by_name("busy"), Some(1));
+    }
+
+    #[test]
+    fn rename_updates_lookup() {
+        let mut sm = init_sm();
+
+        sm.rename_state(1, "working").unwrap();
+        assert_eq!(sm.state(1).unwrap().name, "working");
+        assert_eq!(sm.state_by_name("busy"), None);
+        assert_eq!(sm.state_by_name("working"), Some(1));
+
+        sm.rename_state(1, "working").unwrap();
+        assert_eq!(sm.rename_state(1, "idle"), Err(FsmError::DuplicateStateName));
+        assert_eq!(sm.rename_state(7, "other"), Err(FsmError::StateIndexOutOfBounds));
+        sm.remove_state(2).unwrap();
+        assert_eq!(sm.rename_state(2, "other"), Err(FsmError::StateIsEmpty));
+    }
+
+    #[test]
+    fn duplicate_name_rejected() {
+        let mut sm = init_sm();
+
+        let duplicate: State<u32> = State::new("idle", State::noop_init, State::noop_exec);
+        assert_eq!(sm.add_state(duplicate).err(), Some(FsmError::DuplicateStateName));
+        assert_eq!(sm.state_by_name("idle"), Some(0));
+    }
+
+    #[test]
+    fn transition_lookup_by_name() {
+        let mut sm = init_sm();
+        let (idle, busy, done) = (0, 1, 2);
+        declare_data_type!(u32);
+        new_transition!(sm, idle, busy, &always, &noop_done);
+        new_transition!(sm, busy, done, &always, &noop_done);
+
+        assert_eq!(sm.transition_by_name("busy__done"), Some((1, 2)));
+        assert_eq!(sm.transition_by_name("idle__busy"), Some((0, 1)));
+        assert_eq!(sm.transition_by_name("done__idle"), None);
+
+        sm.remove_transition(1, 2).unwrap();
+        assert_eq!(sm.transition_by_name("busy__done"), None);
+    }
+}
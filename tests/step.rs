@@ -0,0 +1,242 @@
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+
+    #[derive(Clone, Copy)]
+    struct Counter {
+        pub ticks: u32,
+    }
+
+    fn init_sm() -> StateMachine<Counter> {
+        let counter = Counter { ticks: 0 };
+
+        declare_data_type!(Counter);
+        declare_state_machine!(sm, counter, 2);
+
+        new_state!(sm, idle, &State::noop_init, &tick_exec);
+        new_state!(sm, done, &State::noop_init, &tick_exec);
+
+        new_transition!(sm, idle, done, &ticks_reached, &noop_done);
+
+        sm
+    }
+
+    fn tick_exec(_s: &State<Counter>, data: &mut Counter) -> Result<(), FsmError> {
+        data.ticks += 1;
+        Ok(())
+    }
+
+    fn ticks_reached(_t: &Transition<Counter>, data: &Counter) -> bool {
+        data.ticks >= 2
+    }
+
+    #[test]
+    fn errors_without_active_state() {
+        let mut sm = init_sm();
+        assert_eq!(sm.run(), StepOutcome::Errored(FsmError::NoActiveState.into()));
+        assert_eq!(sm.run_until_stable(10), Err(FsmError::NoActiveState.into()));
+    }
+
+    #[test]
+    fn stayed_then_transitioned() {
+        let mut sm = init_sm();
+        sm.set_active_state(0).unwrap();
+
+        assert_eq!(sm.run(), StepOutcome::Stayed(0));
+        assert_eq!(sm.run(), StepOutcome::Transitioned { from: 0, to: 1, via: String::from("idle__done") });
+        assert_eq!(sm.run(), StepOutcome::Stayed(1));
+        assert_eq!(sm.data().ticks, 3);
+    }
+
+    #[test]
+    fn seed_data_between_steps() {
+        let mut sm = init_sm();
+        sm.set_active_state(0).unwrap();
+
+        sm.data_mut().ticks = 10;
+        assert_eq!(sm.run(), StepOutcome::Transitioned { from: 0, to: 1, via: String::from("idle__done") });
+        assert_eq!(sm.data().ticks, 11);
+    }
+
+    #[test]
+    fn current_state_introspection() {
+        let mut sm = init_sm();
+        assert_eq!(sm.current_state_index(), None);
+        assert_eq!(sm.current_state_name(), None);
+
+        sm.set_active_state(0).unwrap();
+        sm.run();
+        sm.run();
+        assert_eq!(sm.current_state_index(), Some(1));
+        assert_eq!(sm.current_state().map(|s| s.name.as_str()), Some("done"));
+        assert_eq!(sm.current_state_name(), Some("done"));
+    }
+
+    #[test]
+    fn is_in_state_by_name() {
+        let mut sm = init_sm();
+        assert!(!sm.is_in_state("idle"));
+
+        sm.set_active_state(0).unwrap();
+        assert!(sm.is_in_state("idle"));
+        assert!(!sm.is_in_state("done"));
+    }
+
+    #[test]
+    fn run_steps_stops_on_error() {
+        let mut sm = init_sm();
+        assert_eq!(sm.run_steps(5), vec![StepOutcome::Errored(FsmError::NoActiveState.into())]);
+
+        sm.set_active_state(0).unwrap();
+        assert_eq!(sm.run_steps(3), vec![
+            StepOutcome::Stayed(0),
+            StepOutcome::Transitioned { from: 0, to: 1, via: String::from("idle__done") },
+            StepOutcome::Stayed(1),
+        ]);
+    }
+
+    #[test]
+    fn run_until_stable_counts_steps() {
+        let mut sm = init_sm();
+        sm.set_active_state(0).unwrap();
+        assert_eq!(sm.run_until_stable(10), Ok(1));
+
+        sm.data_mut().ticks = 5;
+        sm.set_active_state(0).unwrap();
+        assert_eq!(sm.run_until_stable(10), Ok(2));
+        assert_eq!(sm.current_state_index(), Some(1));
+    }
+
+    #[test]
+    fn reset_returns_to_initial_state() {
+        let mut sm = init_sm();
+        sm.set_initial(0).unwrap();
+        sm.reset();
+        sm.run_steps(3);
+        assert_eq!(sm.current_state_index(), Some(1));
+
+        sm.reset();
+        assert_eq!(sm.current_state_index(), Some(0));
+        assert_eq!(sm.data().ticks, 3);
+
+        sm.reset_with(Counter { ticks: 0 });
+        assert_eq!(sm.run(), StepOutcome::Stayed(0));
+        assert_eq!(sm.data().ticks, 1);
+    }
+
+    #[test]
+    fn set_active_state_by_name() {
+        let mut sm = init_sm();
+        assert_eq!(sm.state_by_name("done"), Some(1));
+
+        sm.set_active_state_by_name("done").unwrap();
+        assert_eq!(sm.current_state_index(), Some(1));
+        assert_eq!(sm.set_active_state_by_name("missing"), Err(FsmError::StateIsEmpty));
+        assert_eq!(sm.current_state_index(), Some(1));
+    }
+
+    #[test]
+    fn fuel_bounds_steps() {
+        let mut sm = init_sm();
+        sm.set_active_state(0).unwrap();
+        assert_eq!(sm.fuel(), None);
+
+        sm.set_fuel(2);
+        assert_eq!(sm.run(), StepOutcome::Stayed(0));
+        assert_eq!(sm.run(), StepOutcome::Transitioned { from: 0, to: 1, via: String::from("idle__done") });
+        assert_eq!(sm.run(), StepOutcome::Errored(FsmError::OutOfFuel.into()));
+        assert_eq!(sm.data().ticks, 2);
+
+        sm.refuel(1);
+        assert_eq!(sm.run(), StepOutcome::Stayed(1));
+        assert_eq!(sm.fuel(), Some(0));
+    }
+
+    #[test]
+    fn steps_iterate_until_error() {
+        let mut sm = init_sm();
+        assert_eq!(sm.steps().count(), 1);
+
+        sm.set_active_state(0).unwrap();
+        let transitioned = sm.steps().take(5).filter(|outcome| matches!(outcome, StepOutcome::Transitioned { .. })).count();
+        assert_eq!(transitioned, 1);
+        assert_eq!(sm.data().ticks, 5);
+
+        sm.set_fuel(2);
+        assert_eq!(sm.steps().collect::<Vec<_>>(), vec![
+            StepOutcome::Stayed(1),
+            StepOutcome::Stayed(1),
+            StepOutcome::Errored(FsmError::OutOfFuel.into()),
+        ]);
+    }
+
+    fn panic_once(_s: &State<Counter>, data: &mut Counter) -> Result<(), FsmError> {
+        data.ticks += 1;
+        if data.ticks == 1 {
+            panic!("exec blew up");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn panic_does_not_leave_machine_running() {
+        let counter = Counter { ticks: 0 };
+
+        declare_data_type!(Counter);
+        declare_state_machine!(sm, counter, 1);
+
+        new_state!(sm, flaky, &State::noop_init, &panic_once);
+        sm.set_active_state(flaky).unwrap();
+
+        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| sm.run()));
+        assert_eq!(sm.run(), StepOutcome::Stayed(flaky.0));
+    }
+
+    fn fail_every_other(_s: &State<Counter>, data: &mut Counter) -> Result<(), FsmError> {
+        data.ticks += 1;
+        if data.ticks % 2 == 1 {
+            return Err(FsmError::StateIsEmpty);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn steps_continue_past_routed_errors() {
+        let counter = Counter { ticks: 0 };
+
+        declare_data_type!(Counter);
+        declare_state_machine!(sm, counter, 1);
+
+        new_state!(sm, flaky, &State::noop_init, &fail_every_other);
+        sm.set_active_state(flaky).unwrap();
+
+        sm.set_error_callbacks(|_e, _data| None, |_e, _data| Some(Destination::Index(0)));
+        assert_eq!(sm.steps().take(4).count(), 4);
+        assert_eq!(sm.data().ticks, 4);
+
+        sm.set_error_callbacks(|_e, _data| None, |_e, _data| None);
+        assert_eq!(sm.steps().take(4).count(), 1);
+    }
+
+    #[test]
+    fn steps_continue_past_routed_structural_errors() {
+        let counter = Counter { ticks: 0 };
+
+        declare_data_type!(Counter);
+        declare_state_machine!(sm, counter, 1);
+
+        new_state!(sm, idle, &State::noop_init, &State::noop_exec);
+        sm.set_active_state(idle).unwrap();
+        sm.set_stuck_threshold(Some(1));
+
+        sm.set_error_callbacks(|_e, _data| None, |_e, _data| Some(Destination::Index(0)));
+        assert_eq!(sm.steps().take(10).count(), 10);
+
+        sm.set_error_callbacks(|_e, _data| None, |_e, _data| None);
+        let outcomes: Vec<_> = sm.steps().take(10).collect();
+        assert_eq!(outcomes.last(), Some(&StepOutcome::Errored(FsmError::StuckInState.into())));
+
+        sm.set_fuel(0);
+        assert_eq!(sm.steps().take(10).count(), 1);
+    }
+}
@@ -0,0 +1,73 @@
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+
+    #[derive(Clone)]
+    struct Watch {
+        pub stuck_in: Vec<usize>,
+    }
+
+    fn ignore(_e: FsmErrorKind, _context: ErrorContext, _data: &mut Watch) -> Option<Destination> {
+        None
+    }
+
+    fn record_stuck(e: FsmErrorKind, context: ErrorContext, data: &mut Watch) -> Option<Destination> {
+        if e == FsmErrorKind::Structural(FsmError::StuckInState) {
+            data.stuck_in.push(context.state);
+        }
+        None
+    }
+
+    #[test]
+    fn watchdog_flags_stuck_state() {
+        let watch = Watch { stuck_in: Vec::new() };
+
+        declare_data_type!(Watch);
+        declare_state_machine!(sm, watch, 2);
+
+        new_state!(sm, waiting, &State::noop_init, &State::noop_exec);
+        new_state!(sm, ready, &State::noop_init, &State::noop_exec);
+        new_transition!(sm, waiting, ready, &never, &noop_done);
+
+        sm.set_error_callbacks_ctx(ignore, record_stuck);
+        sm.set_stuck_threshold(Some(2));
+        sm.set_active_state(waiting).unwrap();
+
+        assert_eq!(sm.run(), StepOutcome::Stayed(waiting.0));
+        assert_eq!(sm.run(), StepOutcome::Stayed(waiting.0));
+        assert_eq!(sm.run(), StepOutcome::Errored(FsmError::StuckInState.into()));
+        assert_eq!(sm.data().stuck_in, vec![waiting.0]);
+        assert_eq!(sm.run(), StepOutcome::Stayed(waiting.0));
+    }
+
+    fn buffer_full(_t: &Transition<Watch>, data: &Watch) -> Result<(), &'static str> {
+        if data.stuck_in.len() >= 2 { Ok(()) } else { Err("buffer not full") }
+    }
+
+    #[test]
+    fn blocked_guards_report_reasons() {
+        let watch = Watch { stuck_in: Vec::new() };
+
+        declare_data_type!(Watch);
+        declare_state_machine!(sm, watch, 2);
+
+        new_state!(sm, waiting, &State::noop_init, &State::noop_exec);
+        new_state!(sm, ready, &State::noop_init, &State::noop_exec);
+        let flush: Transition<Watch> = Transition::with_reason("flush", waiting, ready, buffer_full, noop_done);
+        sm.add_transition(flush, waiting, ready).unwrap();
+
+        let reports = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let sink = reports.clone();
+        sm.set_blocked_observer(move |state, name, reason, _data: &Watch| {
+            sink.borrow_mut().push(format!("{} {} blocked: {}", state, name, reason));
+        });
+        sm.set_active_state(waiting).unwrap();
+
+        assert_eq!(sm.run(), StepOutcome::Stayed(waiting.0));
+        assert_eq!(*reports.borrow(), vec![String::from("0 flush blocked: buffer not full")]);
+
+        sm.data_mut().stuck_in = vec![waiting.0, waiting.0];
+        assert_eq!(sm.run(), StepOutcome::Transitioned { from: waiting.0, to: ready.0, via: String::from("flush") });
+        assert_eq!(reports.borrow().len(), 1);
+    }
+}
@@ -0,0 +1,68 @@
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+
+    #[derive(Clone, Default)]
+    struct Job {
+        pub steps: Vec<&'static str>,
+    }
+
+    fn fetch(_s: &State<Job>, data: &mut Job) -> Result<(), FsmError> {
+        data.steps.push("fetch");
+        Ok(())
+    }
+
+    fn parse(_s: &State<Job>, data: &mut Job) -> Result<(), FsmError> {
+        data.steps.push("parse");
+        Ok(())
+    }
+
+    fn child_finished(_t: &Transition<Job>, data: &Job) -> bool {
+        data.steps.ends_with(&["parse"])
+    }
+
+    fn child_sm() -> StateMachine<Job> {
+        let mut child = unstarted_child_sm();
+        child.set_initial(0).unwrap();
+        child
+    }
+
+    fn unstarted_child_sm() -> StateMachine<Job> {
+        let mut child = StateMachine::new(Job::default(), 2);
+        let fetching = child.add_state(State::new("fetching", State::noop_init, fetch)).unwrap();
+        let parsing = child.add_state(State::new("parsing", State::noop_init, parse)).unwrap();
+        child.add_transition(Transition::new("fetched", fetching, parsing, always, noop_done), fetching, parsing).unwrap();
+        child.mark_final(parsing).unwrap();
+        child
+    }
+
+    #[test]
+    fn child_runs_on_parent_data() {
+        let mut sm = StateMachine::new(Job::default(), 2);
+        let working = sm.add_state(State::sub_machine("working", child_sm(), 10).unwrap()).unwrap();
+        let done = sm.add_state(State::new("done", State::noop_init, State::noop_exec)).unwrap();
+        sm.add_transition(Transition::new("finished", working, done, child_finished, noop_done), working, done).unwrap();
+
+        sm.set_active_state(working).unwrap();
+        assert_eq!(sm.run(), StepOutcome::Transitioned { from: working.0, to: done.0, via: String::from("finished") });
+        assert_eq!(sm.data().steps, vec!["fetch", "parse"]);
+    }
+
+    #[test]
+    fn child_without_initial_state_starts_from_active() {
+        let mut child = unstarted_child_sm();
+        child.set_active_state(0).unwrap();
+
+        let mut sm = StateMachine::new(Job::default(), 1);
+        let working = sm.add_state(State::sub_machine("working", child, 10).unwrap()).unwrap();
+        sm.set_active_state(working).unwrap();
+        assert_eq!(sm.run(), StepOutcome::Stayed(working.0));
+        assert_eq!(sm.data().steps, vec!["fetch", "parse"]);
+    }
+
+    #[test]
+    fn child_without_start_state_is_rejected() {
+        let child: StateMachine<Job> = StateMachine::new(Job::default(), 1);
+        assert_eq!(State::sub_machine("working", child, 10).err(), Some(FsmError::NoActiveState));
+    }
+}
@@ -0,0 +1,33 @@
+#![cfg(feature = "std")]
+
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+    use std::time::Duration;
+
+    #[test]
+    fn timeout_fires_after_duration() {
+        let data = 0;
+
+        declare_data_type!(u32);
+        declare_state_machine!(sm, data, 2);
+
+        new_state!(sm, waiting, &State::noop_init, &State::noop_exec);
+        new_state!(sm, timed_out, &State::noop_init, &State::noop_exec);
+
+        let t: Transition<u32> = Transition::with_timeout("expired", waiting, timed_out, Duration::from_millis(50), never, noop_done);
+        sm.add_transition(t, waiting, timed_out).unwrap();
+
+        let clock = MockClock::new();
+        sm.set_clock(clock.clone());
+
+        sm.set_active_state(waiting).unwrap();
+        assert_eq!(sm.run(), StepOutcome::Stayed(waiting.0));
+
+        clock.advance(Duration::from_millis(49));
+        assert_eq!(sm.run(), StepOutcome::Stayed(waiting.0));
+
+        clock.advance(Duration::from_millis(1));
+        assert_eq!(sm.run(), StepOutcome::Transitioned { from: waiting.0, to: timed_out.0, via: String::from("expired") });
+    }
+}
@@ -0,0 +1,99 @@
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+    use std::rc::Rc;
+
+    fn init_sm() -> StateMachine<u32> {
+        let data = 0;
+
+        declare_data_type!(u32);
+        declare_state_machine!(sm, data, 4);
+
+        new_state!(sm, idle, &State::noop_init, &State::noop_exec);
+        new_state!(sm, busy, &State::noop_init, &State::noop_exec);
+        new_state!(sm, failed, &State::noop_init, &State::noop_exec);
+
+        new_transition!(sm, idle, busy, &always, &noop_done);
+        new_transition!(sm, busy, idle, &always, &noop_done);
+        new_transition!(sm, busy, failed, &always, &noop_done);
+
+        sm
+    }
+
+    #[test]
+    fn topology_lists_states_and_transitions() {
+        let mut sm = init_sm();
+        sm.set_active_state(1).unwrap();
+
+        let topology = sm.topology();
+        assert_eq!(topology.num_states, 3);
+        assert_eq!(topology.active_state, Some(1));
+        assert_eq!(topology.states, vec![
+            Some(String::from("idle")),
+            Some(String::from("busy")),
+            Some(String::from("failed")),
+        ]);
+        assert_eq!(topology.transitions, vec![
+            (0, 1, String::from("idle__busy")),
+            (1, 0, String::from("busy__idle")),
+            (1, 2, String::from("busy__failed")),
+        ]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn topology_json_round_trip() {
+        let topology = init_sm().topology();
+
+        let json = serde_json::to_string(&topology).unwrap();
+        let parsed: FsmTopology = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, topology);
+    }
+
+    #[test]
+    fn iterate_populated_states_and_transitions() {
+        let mut sm = init_sm();
+        sm.remove_state(0).unwrap();
+
+        let states: Vec<(usize, &str)> = sm.states_iter().map(|(i, s)| (i, s.name.as_str())).collect();
+        assert_eq!(states, vec![(1, "busy"), (2, "failed")]);
+
+        let transitions: Vec<(usize, usize, &str)> = sm.transitions_iter().map(|(src, dst, t)| (src, dst, t.name.as_str())).collect();
+        assert_eq!(transitions, vec![(1, 2, "busy__failed")]);
+    }
+
+    fn resolve_state(name: &str) -> Option<StateCallbacks<u32>> {
+        match name {
+            "idle" | "busy" | "failed" => Some((Rc::new(State::noop_init), Rc::new(State::noop_exec))),
+            _ => None,
+        }
+    }
+
+    fn resolve_transition(name: &str) -> Option<TransitionCallbacks<u32>> {
+        match name {
+            "idle__busy" | "busy__idle" | "busy__failed" => Some((Rc::new(always), Rc::new(noop_done))),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn rebuild_from_topology() {
+        let mut original = init_sm();
+        original.remove_state(2).unwrap();
+        original.set_active_state(1).unwrap();
+        let topology = original.topology();
+
+        let mut sm = StateMachine::from_topology(0, &topology, resolve_state, resolve_transition).unwrap();
+        assert_eq!(sm.topology(), topology);
+        assert_eq!(sm.run(), StepOutcome::Transitioned { from: 1, to: 0, via: String::from("busy__idle") });
+    }
+
+    #[test]
+    fn unknown_callback_name() {
+        let mut topology = init_sm().topology();
+        topology.states[0] = Some(String::from("mystery"));
+
+        let sm = StateMachine::from_topology(0, &topology, resolve_state, resolve_transition);
+        assert_eq!(sm.err(), Some(FsmError::UnresolvedName));
+    }
+}
@@ -0,0 +1,63 @@
+#![cfg(feature = "std")]
+
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+    use std::time::Duration;
+
+    fn count_done(_t: &Transition<u32>, data: &mut u32) -> Result<(), FsmError> {
+        *data += 1;
+        Ok(())
+    }
+
+    fn init_sm() -> StateMachine<u32> {
+        let data = 0;
+
+        declare_data_type!(u32);
+        declare_state_machine!(sm, data, 3);
+
+        new_state!(sm, idle, &State::noop_init, &State::noop_exec);
+        new_state!(sm, busy, &State::noop_init, &State::noop_exec);
+        new_state!(sm, done, &State::noop_init, &State::noop_exec);
+
+        new_transition!(sm, idle, busy, &always, &count_done);
+        new_transition!(sm, busy, done, &always, &count_done);
+
+        sm
+    }
+
+    #[test]
+    fn record_and_replay() {
+        let mut sm = init_sm();
+        let clock = MockClock::new();
+        sm.set_clock(clock.clone());
+        sm.record_trace(true);
+
+        sm.set_active_state(0).unwrap();
+        clock.advance(Duration::from_secs(1));
+        sm.run();
+        clock.advance(Duration::from_secs(1));
+        sm.run();
+
+        let trace = sm.take_trace();
+        assert_eq!(trace, vec![
+            TraceEntry { at: Duration::from_secs(1), src: 0, dst: 1, transition: String::from("idle__busy") },
+            TraceEntry { at: Duration::from_secs(2), src: 1, dst: 2, transition: String::from("busy__done") },
+        ]);
+        assert!(sm.take_trace().is_empty());
+
+        let mut fresh = init_sm();
+        fresh.replay(&trace).unwrap();
+        assert_eq!(fresh.current_state_index(), Some(2));
+        assert_eq!(*fresh.data(), 2);
+    }
+
+    #[test]
+    fn nothing_recorded_by_default() {
+        let mut sm = init_sm();
+        sm.set_active_state(0).unwrap();
+        sm.run();
+
+        assert!(sm.take_trace().is_empty());
+    }
+}
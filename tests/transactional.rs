@@ -0,0 +1,47 @@
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Ledger {
+        pub entries: Vec<u32>,
+    }
+
+    fn partial_commit(_t: &Transition<Ledger>, data: &mut Ledger) -> Result<(), FsmError> {
+        data.entries.push(1);
+        Err(FsmError::TransitionIsEmpty)
+    }
+
+    fn init_sm(transactional: bool) -> StateMachine<Ledger> {
+        let ledger = Ledger { entries: Vec::new() };
+
+        declare_data_type!(Ledger);
+        declare_state_machine!(sm, ledger, 2);
+
+        new_state!(sm, open, &State::noop_init, &State::noop_exec);
+        new_state!(sm, closed, &State::noop_init, &State::noop_exec);
+
+        new_transition!(sm, open, closed, &always, &partial_commit);
+
+        sm.set_transactional(transactional);
+        sm.set_active_state(open).unwrap();
+        sm
+    }
+
+    #[test]
+    fn failed_done_rolls_back_data() {
+        let mut sm = init_sm(true);
+
+        assert_eq!(sm.run(), StepOutcome::Errored(FsmErrorKind::User(FsmError::TransitionIsEmpty)));
+        assert!(sm.data().entries.is_empty());
+        assert_eq!(sm.current_state_index(), Some(0));
+    }
+
+    #[test]
+    fn failed_done_keeps_side_effects_by_default() {
+        let mut sm = init_sm(false);
+
+        sm.run();
+        assert_eq!(sm.data().entries, vec![1]);
+    }
+}
@@ -0,0 +1,45 @@
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum IoError {
+        Timeout { after_ms: u32 },
+    }
+
+    #[derive(Clone)]
+    struct Link {
+        pub last_error: Option<IoError>,
+    }
+
+    fn connect(_s: &State<Link, IoError>, _data: &mut Link) -> Result<(), IoError> {
+        Err(IoError::Timeout { after_ms: 500 })
+    }
+
+    fn remember_error(error: FsmErrorKind<IoError>, data: &mut Link) -> Option<Destination> {
+        if let FsmErrorKind::User(e) = error {
+            data.last_error = Some(e);
+        }
+        None
+    }
+
+    fn route_error(error: FsmErrorKind<IoError>, _data: &mut Link) -> Option<Destination> {
+        match error {
+            FsmErrorKind::User(IoError::Timeout { .. }) => Some(Destination::Name(String::from("offline"))),
+            FsmErrorKind::Structural(_) => None,
+        }
+    }
+
+    #[test]
+    fn user_errors_reach_error_callback() {
+        let mut sm: StateMachine<Link, IoError> = StateMachine::new(Link { last_error: None }, 2);
+        let connecting = sm.add_state(State::new("connecting", connect, State::noop_exec)).unwrap();
+        let offline = sm.add_state(State::new("offline", State::noop_init, State::noop_exec)).unwrap();
+        sm.set_error_callbacks(remember_error, route_error);
+        sm.set_active_state(connecting).unwrap();
+
+        assert_eq!(sm.run(), StepOutcome::Errored(FsmErrorKind::User(IoError::Timeout { after_ms: 500 })));
+        assert_eq!(sm.data().last_error, Some(IoError::Timeout { after_ms: 500 }));
+        assert_eq!(sm.current_state_id(), Some(offline));
+    }
+}
@@ -0,0 +1,30 @@
+#[cfg(test)]
+mod tests {
+    use call_fsm::{*};
+
+    #[test]
+    fn visits_counted_per_entry() {
+        let data = 0;
+
+        declare_data_type!(u32);
+        declare_state_machine!(sm, data, 2);
+
+        new_state!(sm, ping, &State::noop_init, &State::noop_exec);
+        new_state!(sm, pong, &State::noop_init, &State::noop_exec);
+
+        new_transition!(sm, ping, pong, &always, &noop_done);
+        new_transition!(sm, pong, ping, &always, &noop_done);
+
+        sm.set_active_state(ping).unwrap();
+        sm.run_steps(3);
+
+        assert_eq!(sm.visit_count(ping), 2);
+        assert_eq!(sm.visit_count(pong), 2);
+        assert_eq!(sm.visit_count(7), 0);
+
+        sm.reset_stats();
+        assert_eq!(sm.visit_count(ping), 0);
+        sm.run();
+        assert_eq!(sm.visit_count(ping), 1);
+    }
+}